@@ -1,11 +1,11 @@
-use super::application::BasicApplication;
+use super::{application::BasicApplication, vertex::CubeInstance};
 use brics::{
     application::ApplicationController,
     handle::{camera::CameraHandle, shape::ShapeHandle},
     pipeline::Geometry,
     script::*,
 };
-use cgmath::Vector3;
+use cgmath::{Matrix4, Vector3};
 
 pub struct BasicController {
     scripts: Vec<Box<dyn Script<BasicApplication>>>,
@@ -15,6 +15,7 @@ impl ApplicationController<BasicApplication> for BasicController {
     fn new(app: &mut BasicApplication) -> Self {
         let geometry = app.get_cube_geometry();
         create_ground(app, &geometry);
+        create_cube_field(app, &geometry);
 
         Self {
             scripts: vec![
@@ -25,7 +26,8 @@ impl ApplicationController<BasicApplication> for BasicController {
         }
     }
 
-    fn step(&mut self, app: &mut BasicApplication, _: f32) {      
+    fn step(&mut self, app: &mut BasicApplication, _: f32) {
+        app.visual.begin_tick();
         for script in self.scripts.iter_mut() {
             script.update(app);
         }
@@ -38,6 +40,30 @@ fn create_ground(app: &mut BasicApplication, geometry: &Geometry) {
     ground.borrow_mut().translate(Vector3::new(0.0, -0.5, 0.0));
 }
 
+// A single instanced entity drawing a grid of small cubes above the ground.
+fn create_cube_field(app: &mut BasicApplication, geometry: &Geometry) {
+    let grid_size = 64;
+    let spacing = 9.0 / grid_size as f32;
+
+    let instances: Vec<CubeInstance> = (0..grid_size * grid_size)
+        .map(|index| {
+            let (x, z) = ((index % grid_size) as f32, (index / grid_size) as f32);
+            let offset = Vector3::new(
+                (x - 0.5 * grid_size as f32) * spacing,
+                -0.4,
+                (z - 0.5 * grid_size as f32) * spacing,
+            );
+
+            CubeInstance::new(
+                Matrix4::from_translation(offset) * Matrix4::from_scale(0.25 * spacing),
+                Vector3::new(x / grid_size as f32, 0.4, z / grid_size as f32),
+            )
+        })
+        .collect();
+
+    app.visual.create_instanced_shape_entity(geometry, &instances);
+}
+
 fn get_main_camera_script(
     app: &mut BasicApplication,
 ) -> ObjectController<CameraHandle, BasicApplication> {
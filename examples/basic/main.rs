@@ -8,8 +8,8 @@ mod visual;
 use application::BasicApplication;
 use controller::BasicController;
 
-use brics::run::run;
+use brics::{config::GraphicsConfig, run::run};
 
 fn main() {
-    run::<BasicApplication, BasicController>(60);
+    run::<BasicApplication, BasicController>(60, GraphicsConfig::new());
 }
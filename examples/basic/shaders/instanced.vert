@@ -0,0 +1,42 @@
+#version 450
+
+layout(location = 0) in vec3 a_Pos;
+layout(location = 1) in vec3 a_Norm;
+layout(location = 2) in mat4 a_InstanceModel;
+layout(location = 6) in vec3 a_InstanceColor;
+
+layout(location = 0) out vec3 v_out_Color;
+layout(location = 1) out vec3 v_out_Norm;
+layout(location = 2) out vec3 v_out_FragPos;
+layout(location = 3) out vec3 v_out_CamPosition;
+layout(location = 4) out mat4 m_out_PVLight;
+
+layout(set = 0, binding = 0) uniform u_Camera {
+    mat4 m_in_PV;
+    vec3 v_in_CamPosition;
+};
+
+layout(set = 0, binding = 1) uniform u_ObjectState {
+    mat4 m_in_Model;
+    vec3 v_in_Color;
+};
+
+layout(set = 0, binding = 3) uniform u_LightCamera {
+    mat4 m_in_PVLight;
+    vec3 v_in_CamPositionLight;
+};
+
+void main() {
+    // the shape handle places the whole group, each instance places itself within it
+    mat4 model = m_in_Model * a_InstanceModel;
+
+    m_out_PVLight = m_in_PVLight;
+
+    v_out_Color = v_in_Color * a_InstanceColor;
+    v_out_Norm = normalize(transpose(inverse(mat3(model))) * a_Norm);
+    v_out_CamPosition = v_in_CamPosition;
+
+    vec4 pos = model * vec4(a_Pos, 1.0);
+    v_out_FragPos = vec3(pos);
+    gl_Position = m_in_PV * pos;
+}
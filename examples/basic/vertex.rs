@@ -1,5 +1,8 @@
-use cgmath::{Point3, Vector3};
-use brics::pipeline::Vertex;
+use cgmath::{Matrix4, Point3, Vector3};
+use brics::{
+    mesh::{FromMeshVertex, MeshVertex},
+    pipeline::Vertex,
+};
 
 pub struct VertexBasic {
     _position: Point3<f32>,
@@ -20,3 +23,38 @@ impl Vertex for VertexBasic {
         vec![wgpu::VertexFormat::Float3, wgpu::VertexFormat::Float3]
     }
 }
+
+impl FromMeshVertex for VertexBasic {
+    fn from_mesh_vertex(vertex: &MeshVertex) -> Self {
+        Self::new(vertex.position, vertex.normal)
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[repr(C)]
+pub struct CubeInstance {
+    _model: Matrix4<f32>,
+    _color: Vector3<f32>,
+}
+
+impl CubeInstance {
+    pub fn new(model: Matrix4<f32>, color: Vector3<f32>) -> Self {
+        Self {
+            _model: model,
+            _color: color,
+        }
+    }
+}
+
+impl Vertex for CubeInstance {
+    fn get_attribute_formats() -> Vec<wgpu::VertexFormat> {
+        vec![
+            wgpu::VertexFormat::Float4,
+            wgpu::VertexFormat::Float4,
+            wgpu::VertexFormat::Float4,
+            wgpu::VertexFormat::Float4,
+            wgpu::VertexFormat::Float3,
+        ]
+    }
+}
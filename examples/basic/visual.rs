@@ -1,6 +1,4 @@
-use std::ops::Deref;
-
-use super::vertex::VertexBasic;
+use super::vertex::{CubeInstance, VertexBasic};
 
 use brics::{
     application::{Application, Visual},
@@ -8,6 +6,7 @@ use brics::{
         sampler::{SamplerAddressMode, SamplerFilterMode},
         texture::TextureBinding,
     },
+    config::GraphicsConfig,
     graphics::GraphicsManager,
     handle::{
         camera::{CameraHandle, CameraHandleLayout},
@@ -31,6 +30,7 @@ pub struct BasicVisual {
     renderer: Renderer,
 
     pipeline_id: (u32, u32),
+    instanced_pipeline_id: (u32, u32),
     shadow_pipeline_id: (u32, u32),
 
     /*------------------*/
@@ -38,8 +38,8 @@ pub struct BasicVisual {
     shape_handle_layout: ShapeHandleLayout,
 
     /*------------------*/
-    depth_texture_handle: TextureHandle,
-    depth_sampler_handle: SamplerHandle,
+    depth_texture_handle: RcBinding<TextureHandle>,
+    depth_sampler_handle: RcBinding<SamplerHandle>,
     light_camera: RcBinding<CameraHandle>,
 
     /*------------------*/
@@ -49,9 +49,12 @@ pub struct BasicVisual {
 }
 
 impl Visual for BasicVisual {
-    fn new(event_loop: &winit::event_loop::EventLoop<()>) -> Self {
+    fn new(
+        event_loop: Option<&winit::event_loop::EventLoop<()>>,
+        config: &GraphicsConfig,
+    ) -> Self {
         let graphics: GraphicsManager =
-            futures::executor::block_on(GraphicsManager::new(event_loop, None));
+            futures::executor::block_on(GraphicsManager::from_event_loop(event_loop, config));
 
         let camera_handle_layout = CameraHandleLayout::new(wgpu::ShaderStage::VERTEX);
         let camera = Self::create_main_camera(&camera_handle_layout, &graphics);
@@ -87,6 +90,16 @@ impl Visual for BasicVisual {
                 .add(&depth_texture_handle_layout)
                 .add(&sampler_handle_layout),
         );
+        let instanced_pipeline = Self::create_instanced_pipeline(
+            &graphics,
+            BindingLayoutEntries::new()
+                .add(&camera_handle_layout)
+                .add(&shape_handle_layout)
+                .add(&light_handle_layout)
+                .add(&camera_handle_layout)
+                .add(&depth_texture_handle_layout)
+                .add(&sampler_handle_layout),
+        );
 
         let texture_view = depth_texture_handle.create_texture_view();
 
@@ -99,19 +112,26 @@ impl Visual for BasicVisual {
         );
         let pipeline_id =
             Self::create_material_render_pass(&graphics, &mut renderer, material_pipeline);
+        let instanced_pipeline_id = (
+            pipeline_id.0,
+            renderer
+                .get_render_pass(pipeline_id.0)
+                .add_pipeline(instanced_pipeline),
+        );
 
         Self {
             graphics,
             renderer,
 
             pipeline_id,
+            instanced_pipeline_id,
             shadow_pipeline_id,
 
             light_handle_layout,
             shape_handle_layout,
 
-            depth_texture_handle,
-            depth_sampler_handle,
+            depth_texture_handle: RcBinding::new(depth_texture_handle),
+            depth_sampler_handle: RcBinding::new(depth_sampler_handle),
             light_camera: RcBinding::new(light_camera),
 
             camera: RcBinding::new(camera),
@@ -120,9 +140,21 @@ impl Visual for BasicVisual {
         }
     }
 
-    fn render(&mut self) {
+    fn render(&mut self, alpha: f32) {
+        self.shapes
+            .iter()
+            .for_each(|shape| shape.borrow_mut().set_interpolation(alpha));
         self.update_bindings();
-        self.graphics.render(&self.renderer);
+        self.renderer
+            .sort_render_pass(self.pipeline_id.0, self.camera.borrow().get_eye());
+        self.graphics
+            .render(&mut self.renderer)
+            .expect("Unrecoverable swap chain error.");
+    }
+
+    fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>) {
+        self.graphics.resize(size, &mut self.renderer);
+        self.camera.borrow_mut().resize(size);
     }
 
     fn request_redraw(&self) {
@@ -136,15 +168,21 @@ impl BasicVisual {
     }
 
     pub fn get_main_camera(&self) -> RcBinding<CameraHandle> {
-        self.camera
+        self.camera.clone()
     }
 
     pub fn get_light_camera(&self) -> RcBinding<CameraHandle> {
-        self.light_camera
+        self.light_camera.clone()
     }
 
     pub fn get_light(&self) -> RcBinding<LightHandle> {
-        self.light
+        self.light.clone()
+    }
+
+    pub fn begin_tick(&self) {
+        self.shapes
+            .iter()
+            .for_each(|shape| shape.borrow_mut().begin_tick());
     }
 
     pub fn create_shape_entity(&mut self, geometry: &Geometry) -> RcBinding<ShapeHandle> {
@@ -154,34 +192,63 @@ impl BasicVisual {
             .get_pipeline(self.pipeline_id.1);
 
         let shape = RcBinding::new(self.shape_handle_layout.create_handle(&self.graphics));
-        self.shapes.push(shape);
-
-        unsafe {
-            self.graphics.add_pipeline_entity(
-                pipeline,
-                geometry,
-                vec![
-                    self.camera.deref(),
-                    shape.deref(),
-                    self.light.deref(),
-                    self.light_camera.deref(),
-                    &self.depth_texture_handle,
-                    &self.depth_sampler_handle,
-                ],
-            );
-        }
+        self.shapes.push(shape.clone());
+
+        self.graphics.add_pipeline_entity(
+            pipeline,
+            geometry,
+            vec![
+                self.camera.share(),
+                shape.share(),
+                self.light.share(),
+                self.light_camera.share(),
+                self.depth_texture_handle.share(),
+                self.depth_sampler_handle.share(),
+            ],
+        );
         let pipeline = self
             .renderer
             .get_render_pass(self.shadow_pipeline_id.0)
             .get_pipeline(self.shadow_pipeline_id.1);
 
-        unsafe {
-            self.graphics.add_pipeline_entity(
+        self.graphics.add_pipeline_entity(
+            pipeline,
+            geometry,
+            vec![self.light_camera.share(), shape.share()],
+        );
+
+        shape
+    }
+
+    // The shape places the whole group; each instance is placed relative to it.
+    pub fn create_instanced_shape_entity(
+        &mut self,
+        geometry: &Geometry,
+        instances: &[CubeInstance],
+    ) -> RcBinding<ShapeHandle> {
+        let pipeline = self
+            .renderer
+            .get_render_pass(self.instanced_pipeline_id.0)
+            .get_pipeline(self.instanced_pipeline_id.1);
+
+        let shape = RcBinding::new(self.shape_handle_layout.create_handle(&self.graphics));
+        self.shapes.push(shape.clone());
+
+        self.graphics
+            .add_instanced_pipeline_entity(
                 pipeline,
                 geometry,
-                vec![self.light_camera.deref(), shape.deref()],
-            );
-        }
+                vec![
+                    self.camera.share(),
+                    shape.share(),
+                    self.light.share(),
+                    self.light_camera.share(),
+                    self.depth_texture_handle.share(),
+                    self.depth_sampler_handle.share(),
+                ],
+                instances,
+            )
+            .expect("Instance type does not match the instanced pipeline.");
 
         shape
     }
@@ -309,10 +376,11 @@ impl BasicVisual {
         graphics: &GraphicsManager,
         entries: BindingLayoutEntries,
     ) -> Pipeline {
-        graphics.create_pipeline::<VertexBasic>(
+        graphics.create_pipeline::<VertexBasic, u16>(
             "examples/basic/shaders/shadow.vert",
             "examples/basic/shaders/shadow.frag",
             entries,
+            wgpu::PrimitiveTopology::TriangleList,
             None,
             Some(wgpu::DepthStencilStateDescriptor {
                 format: wgpu::TextureFormat::Depth32Float,
@@ -335,12 +403,42 @@ impl BasicVisual {
         graphics: &GraphicsManager,
         entries: BindingLayoutEntries,
     ) -> Pipeline {
-        graphics.create_pipeline::<VertexBasic>(
+        graphics.create_pipeline::<VertexBasic, u16>(
             "examples/basic/shaders/material.vert",
             "examples/basic/shaders/material.frag",
             entries,
+            wgpu::PrimitiveTopology::TriangleList,
+            Some(wgpu::ColorStateDescriptor {
+                format: graphics.get_output_format(),
+                color_blend: wgpu::BlendDescriptor::REPLACE,
+                alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                write_mask: wgpu::ColorWrite::ALL,
+            }),
+            Some(wgpu::DepthStencilStateDescriptor {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilStateDescriptor::default(),
+            }),
+            Some(wgpu::RasterizationStateDescriptor {
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: wgpu::CullMode::Back,
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn create_instanced_pipeline(
+        graphics: &GraphicsManager,
+        entries: BindingLayoutEntries,
+    ) -> Pipeline {
+        graphics.create_instanced_pipeline::<VertexBasic, u16, CubeInstance>(
+            "examples/basic/shaders/instanced.vert",
+            "examples/basic/shaders/material.frag",
+            entries,
+            wgpu::PrimitiveTopology::TriangleList,
             Some(wgpu::ColorStateDescriptor {
-                format: GraphicsManager::get_swapchain_color_format(),
+                format: graphics.get_output_format(),
                 color_blend: wgpu::BlendDescriptor::REPLACE,
                 alpha_blend: wgpu::BlendDescriptor::REPLACE,
                 write_mask: wgpu::ColorWrite::ALL,
@@ -367,7 +465,7 @@ impl BasicVisual {
     ) -> (u32, u32) {
         let mut rpass = RenderPass::new();
         rpass.set_depth_attachment(
-            depth_output,
+            AttachmentView::Static(depth_output),
             wgpu::Operations {
                 load: wgpu::LoadOp::Clear(1.0),
                 store: true,
@@ -385,7 +483,7 @@ impl BasicVisual {
         renderer: &mut Renderer,
         material_pipeline: Pipeline,
     ) -> (u32, u32) {
-        let depth_texture_view = graphics.create_depth_texture_view();
+        let depth_texture_view = graphics.create_depth_attachment_view();
         let mut rpass = RenderPass::new();
         rpass
             .set_color_attachment(
@@ -1,26 +1,31 @@
 use winit;
 
+use crate::config::GraphicsConfig;
 
 pub trait Visual {
-    fn new(event_loop: &winit::event_loop::EventLoop<()>) -> Self
+    fn new(event_loop: Option<&winit::event_loop::EventLoop<()>>, config: &GraphicsConfig) -> Self
     where
         Self: Sized;
 
-    fn render(&mut self);
+    fn render(&mut self, alpha: f32);
+
+    fn resize(&mut self, _size: winit::dpi::PhysicalSize<u32>) {}
 
     fn request_redraw(&self);
 }
 
 pub trait Application {
-    fn new(event_loop: &winit::event_loop::EventLoop<()>) -> Self
+    fn new(event_loop: Option<&winit::event_loop::EventLoop<()>>, config: &GraphicsConfig) -> Self
     where
         Self: Sized;
 
     fn handle_input(&mut self, event: &winit::event::WindowEvent);
 
+    fn resize(&mut self, _size: winit::dpi::PhysicalSize<u32>) {}
+
     fn request_redraw(&self);
 
-    fn step(&mut self);
+    fn step(&mut self, alpha: f32);
 }
 
 pub trait ApplicationController<A: Application> {
@@ -1,4 +1,5 @@
 use super::{Binding, BindingLayout};
+use crate::error::Error;
 
 struct BufferBindingLayout {
     visibility: wgpu::ShaderStage,
@@ -79,19 +80,33 @@ impl Binding for UniformBinding {
 
 /*--------------------------------------------------------------------------------------------------*/
 
+// Storage layout seen by the shader (std430):
+//     buffer Instances { uint count; uint _pad[3]; T items[]; };
+// The 16 byte header keeps `items` aligned for any element type up to a vec4.
+const INSTANCE_ARRAY_HEADER_SIZE: wgpu::BufferAddress = 16;
+
 pub struct InstanceArrayBindingLayout {
     buffer_binding_layout: BufferBindingLayout,
+
+    element_size: wgpu::BufferAddress,
+    capacity: u32,
 }
 
 impl InstanceArrayBindingLayout {
-    pub fn new<T>(visibility: wgpu::ShaderStage, n_instances: u32) -> InstanceArrayBindingLayout {
+    pub fn new<T>(visibility: wgpu::ShaderStage, capacity: u32) -> InstanceArrayBindingLayout {
+        let element_size = std::mem::size_of::<T>() as wgpu::BufferAddress;
+        let capacity = capacity.max(1);
+
         InstanceArrayBindingLayout {
             buffer_binding_layout: BufferBindingLayout {
                 visibility,
 
                 usage: wgpu::BufferUsage::STORAGE | wgpu::BufferUsage::COPY_DST,
-                buffer_size: (std::mem::size_of::<T>() as u32 * n_instances) as wgpu::BufferAddress,
+                buffer_size: InstanceArrayBinding::get_buffer_size(element_size, capacity),
             },
+
+            element_size,
+            capacity,
         }
     }
 }
@@ -103,7 +118,9 @@ impl BindingLayout<InstanceArrayBinding> for InstanceArrayBindingLayout {
             visibility: self.buffer_binding_layout.visibility,
             ty: wgpu::BindingType::StorageBuffer {
                 dynamic: false,
-                min_binding_size: None,
+                min_binding_size: wgpu::BufferSize::new(
+                    INSTANCE_ARRAY_HEADER_SIZE + self.element_size,
+                ),
                 readonly: false,
             },
 
@@ -114,28 +131,232 @@ impl BindingLayout<InstanceArrayBinding> for InstanceArrayBindingLayout {
     fn create_binding(&self, device: &wgpu::Device) -> InstanceArrayBinding {
         InstanceArrayBinding {
             buffer: self.buffer_binding_layout.create_buffer(device),
+            usage: self.buffer_binding_layout.usage,
+
+            element_size: self.element_size,
+            capacity: self.capacity,
+            len: 0,
+            generation: 0,
         }
     }
 }
 
 pub struct InstanceArrayBinding {
     buffer: wgpu::Buffer,
+    usage: wgpu::BufferUsage,
+
+    element_size: wgpu::BufferAddress,
+    capacity: u32,
+    len: u32,
+    generation: u32,
 }
 
 impl InstanceArrayBinding {
-    pub fn update<T>(&self, data: &Vec<T>, write_queue: &wgpu::Queue) {
+    // Writes `data.len()` into the header followed by the elements. Returns true if the buffer
+    // had to be reallocated; pipelines rebuild the bind groups of their entities on the next
+    // render when they see the new generation.
+    pub fn update<T>(
+        &mut self,
+        device: &wgpu::Device,
+        data: &[T],
+        write_queue: &wgpu::Queue,
+    ) -> Result<bool, Error> {
+        if std::mem::size_of::<T>() as wgpu::BufferAddress != self.element_size {
+            return Err(Error::Validation(format!(
+                "Element size {} does not match the instance array layout element size {}",
+                std::mem::size_of::<T>(),
+                self.element_size
+            )));
+        }
+
+        let len = data.len() as u32;
+        let reallocated = len > self.capacity;
+        if reallocated {
+            self.capacity = len.next_power_of_two();
+            self.buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size: Self::get_buffer_size(self.element_size, self.capacity),
+                usage: self.usage,
+                mapped_at_creation: false,
+            });
+            self.generation = self.generation.wrapping_add(1);
+        }
+        self.len = len;
+
+        let header: [u32; 4] = [len, 0, 0, 0];
+        let raw_header: &[u8] = unsafe {
+            std::slice::from_raw_parts(
+                header.as_ptr() as *const u8,
+                INSTANCE_ARRAY_HEADER_SIZE as usize,
+            )
+        };
+        write_queue.write_buffer(&self.buffer, 0, raw_header);
+
+        if !data.is_empty() {
+            let raw_data: &[u8] = unsafe {
+                std::slice::from_raw_parts(
+                    data.as_ptr() as *const u8,
+                    data.len() * std::mem::size_of::<T>(),
+                )
+            };
+            write_queue.write_buffer(&self.buffer, INSTANCE_ARRAY_HEADER_SIZE, raw_data);
+        }
+
+        Ok(reallocated)
+    }
+
+    pub fn get_len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn get_capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    fn get_buffer_size(element_size: wgpu::BufferAddress, capacity: u32) -> wgpu::BufferAddress {
+        INSTANCE_ARRAY_HEADER_SIZE + element_size * capacity as wgpu::BufferAddress
+    }
+}
+
+impl Binding for InstanceArrayBinding {
+    fn get_resource(&self) -> wgpu::BindingResource {
+        wgpu::BindingResource::Buffer(self.buffer.slice(..))
+    }
+
+    fn get_generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct StorageBindingLayout {
+    buffer_binding_layout: BufferBindingLayout,
+
+    element_size: wgpu::BufferAddress,
+    len: u32,
+    readonly: bool,
+}
+
+impl StorageBindingLayout {
+    pub fn new<T>(visibility: wgpu::ShaderStage, len: u32, readonly: bool) -> StorageBindingLayout {
+        let element_size = std::mem::size_of::<T>() as wgpu::BufferAddress;
+        let len = len.max(1);
+
+        StorageBindingLayout {
+            buffer_binding_layout: BufferBindingLayout {
+                visibility,
+
+                usage: wgpu::BufferUsage::STORAGE
+                    | wgpu::BufferUsage::COPY_DST
+                    | wgpu::BufferUsage::COPY_SRC,
+                buffer_size: element_size * len as wgpu::BufferAddress,
+            },
+
+            element_size,
+            len,
+            readonly,
+        }
+    }
+}
+
+impl BindingLayout<StorageBinding> for StorageBindingLayout {
+    fn get_entry(&self) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: self.buffer_binding_layout.visibility,
+            ty: wgpu::BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: wgpu::BufferSize::new(self.element_size),
+                readonly: self.readonly,
+            },
+
+            count: None,
+        }
+    }
+
+    fn create_binding(&self, device: &wgpu::Device) -> StorageBinding {
+        StorageBinding {
+            buffer: self.buffer_binding_layout.create_buffer(device),
+
+            element_size: self.element_size,
+            len: self.len,
+        }
+    }
+}
+
+pub struct StorageBinding {
+    buffer: wgpu::Buffer,
+
+    element_size: wgpu::BufferAddress,
+    len: u32,
+}
+
+impl StorageBinding {
+    // Checks that `len` elements of `T` written from element `first` fit the layout.
+    pub fn validate<T>(&self, first: u32, len: usize) -> Result<(), Error> {
+        if std::mem::size_of::<T>() as wgpu::BufferAddress != self.element_size {
+            return Err(Error::Validation(format!(
+                "Element size {} does not match the storage layout element size {}",
+                std::mem::size_of::<T>(),
+                self.element_size
+            )));
+        }
+
+        if first as u64 + len as u64 > self.len as u64 {
+            return Err(Error::Validation(format!(
+                "Storage update of {} elements from element {} is out of bounds, the layout holds {}",
+                len, first, self.len
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn update<T>(&self, first: u32, data: &[T], write_queue: &wgpu::Queue) {
+        assert_eq!(
+            std::mem::size_of::<T>() as wgpu::BufferAddress,
+            self.element_size,
+            "Element size does not match the storage layout."
+        );
+
         let raw_data: &[u8] = unsafe {
             std::slice::from_raw_parts(
-                data.as_slice().as_ptr() as *const u8,
+                data.as_ptr() as *const u8,
                 data.len() * std::mem::size_of::<T>(),
             )
         };
+        self.update_raw(first, raw_data, write_queue);
+    }
 
-        write_queue.write_buffer(&self.buffer, 0, raw_data);
+    pub fn update_raw(&self, first: u32, raw_data: &[u8], write_queue: &wgpu::Queue) {
+        let offset = first as wgpu::BufferAddress * self.element_size;
+        assert_eq!(
+            raw_data.len() as wgpu::BufferAddress % self.element_size,
+            0,
+            "Storage data is not a whole number of elements."
+        );
+        assert!(
+            offset + raw_data.len() as wgpu::BufferAddress
+                <= self.len as wgpu::BufferAddress * self.element_size,
+            "Storage update is out of bounds."
+        );
+
+        if !raw_data.is_empty() {
+            write_queue.write_buffer(&self.buffer, offset, raw_data);
+        }
+    }
+
+    pub fn get_len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn get_buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
     }
 }
 
-impl Binding for InstanceArrayBinding {
+impl Binding for StorageBinding {
     fn get_resource(&self) -> wgpu::BindingResource {
         wgpu::BindingResource::Buffer(self.buffer.slice(..))
     }
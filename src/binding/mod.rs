@@ -12,5 +12,8 @@ pub trait BindingLayout<T: Binding> {
 
 pub trait Binding {
     fn get_resource(&self) -> wgpu::BindingResource;
-}
 
+    fn get_generation(&self) -> u32 {
+        0
+    }
+}
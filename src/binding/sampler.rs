@@ -1,5 +1,7 @@
 use super::{Binding, BindingLayout};
 
+use std::num::NonZeroU8;
+
 pub struct SamplerAddressMode {
     pub u: wgpu::AddressMode,
     pub v: wgpu::AddressMode,
@@ -19,6 +21,10 @@ pub struct SamplerBindingLayout {
     address_mode: SamplerAddressMode,
     filter_mode: SamplerFilterMode,
     compare: Option<wgpu::CompareFunction>,
+
+    lod_min_clamp: f32,
+    lod_max_clamp: f32,
+    anisotropy_clamp: Option<NonZeroU8>,
 }
 
 impl SamplerBindingLayout {
@@ -35,8 +41,26 @@ impl SamplerBindingLayout {
             address_mode,
             filter_mode,
             compare,
+
+            lod_min_clamp: 0.0,
+            lod_max_clamp: std::f32::MAX,
+            anisotropy_clamp: None,
         }
     }
+
+    pub fn set_lod_clamp(&mut self, min: f32, max: f32) -> &mut Self {
+        self.lod_min_clamp = min;
+        self.lod_max_clamp = max;
+
+        self
+    }
+
+    // Valid clamps are 1, 2, 4, 8 and 16; 0 or 1 disables anisotropic filtering.
+    pub fn set_anisotropy_clamp(&mut self, clamp: u8) -> &mut Self {
+        self.anisotropy_clamp = NonZeroU8::new(clamp).filter(|clamp| clamp.get() > 1);
+
+        self
+    }
 }
 
 impl BindingLayout<SamplerBinding> for SamplerBindingLayout {
@@ -52,6 +76,13 @@ impl BindingLayout<SamplerBinding> for SamplerBindingLayout {
     }
 
     fn create_binding(&self, device: &wgpu::Device) -> SamplerBinding {
+        let supports_anisotropy = device
+            .features()
+            .contains(wgpu::Features::SAMPLER_ANISOTROPY);
+        if self.anisotropy_clamp.is_some() && !supports_anisotropy {
+            log::warn!("Sampler anisotropy is not supported by the device, ignoring the clamp");
+        }
+
         SamplerBinding {
             sampler: device.create_sampler(&wgpu::SamplerDescriptor {
                 label: None,
@@ -62,10 +93,10 @@ impl BindingLayout<SamplerBinding> for SamplerBindingLayout {
                 mag_filter: self.filter_mode.mag,
                 min_filter: self.filter_mode.min,
                 mipmap_filter: self.filter_mode.mipmap,
-                lod_min_clamp: 0.0,
-                lod_max_clamp: std::f32::MAX,
+                lod_min_clamp: self.lod_min_clamp,
+                lod_max_clamp: self.lod_max_clamp,
                 compare: self.compare,
-                anisotropy_clamp: None,
+                anisotropy_clamp: self.anisotropy_clamp.filter(|_| supports_anisotropy),
             }),
         }
     }
@@ -1,12 +1,33 @@
 use super::{Binding, BindingLayout};
+use crate::{
+    error::Error,
+    image::ImageData,
+    mipmap::{self, MipmapGenerator, MipmapTarget},
+};
+
+use std::num::NonZeroU32;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TextureAccess {
+    Sampled,
+    ReadOnlyStorage,
+    WriteOnlyStorage,
+}
+
+/*--------------------------------------------------------------------------------------------------*/
 
 pub struct TextureBindingLayout {
     binding: u32,
     visibility: wgpu::ShaderStage,
 
     size: wgpu::Extent3d,
+    mip_level_count: u32,
+    sample_count: u32,
+    view_dimension: wgpu::TextureViewDimension,
 
     format: wgpu::TextureFormat,
+    component_type: wgpu::TextureComponentType,
+    access: TextureAccess,
     usage: wgpu::TextureUsage,
 }
 
@@ -20,11 +41,216 @@ impl TextureBindingLayout {
             binding: 0,
             visibility,
             size,
+            mip_level_count: 1,
+            sample_count: 1,
+            view_dimension: wgpu::TextureViewDimension::D2Array,
 
             format,
+            component_type: Self::get_default_component_type(format),
+            access: TextureAccess::Sampled,
             usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::SAMPLED,
         }
     }
+
+    pub fn new_storage(
+        visibility: wgpu::ShaderStage,
+        size: wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        Self {
+            binding: 0,
+            visibility,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            view_dimension: if size.depth > 1 {
+                wgpu::TextureViewDimension::D2Array
+            } else {
+                wgpu::TextureViewDimension::D2
+            },
+
+            format,
+            component_type: Self::get_default_component_type(format),
+            access: TextureAccess::WriteOnlyStorage,
+            usage: wgpu::TextureUsage::STORAGE
+                | wgpu::TextureUsage::SAMPLED
+                | wgpu::TextureUsage::COPY_SRC,
+        }
+    }
+
+    pub fn new_sampled(visibility: wgpu::ShaderStage, image: &ImageData) -> Self {
+        Self {
+            binding: 0,
+            visibility,
+            size: image.get_size(),
+            mip_level_count: image.get_mip_level_count(),
+            sample_count: 1,
+            view_dimension: if image.get_layers() > 1 {
+                wgpu::TextureViewDimension::D2Array
+            } else {
+                wgpu::TextureViewDimension::D2
+            },
+
+            format: image.get_format(),
+            component_type: Self::get_default_component_type(image.get_format()),
+            access: TextureAccess::Sampled,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        }
+    }
+
+    pub fn new_sampled_cube(visibility: wgpu::ShaderStage, image: &ImageData) -> Self {
+        Self {
+            view_dimension: wgpu::TextureViewDimension::Cube,
+
+            ..Self::new_sampled(visibility, image)
+        }
+    }
+
+    // Allocates the full mip chain; formats the GPU can blit also become render targets.
+    // Call it after setting the view dimension and layer count, which decide the chain length.
+    pub fn enable_mipmaps(&mut self) -> &mut Self {
+        self.mip_level_count =
+            mipmap::get_full_mip_level_count(self.size, self.get_texture_dimension());
+        if MipmapGenerator::supports_format(self.format) {
+            self.usage |= wgpu::TextureUsage::OUTPUT_ATTACHMENT;
+        }
+
+        self
+    }
+
+    pub fn set_view_dimension(&mut self, view_dimension: wgpu::TextureViewDimension) -> &mut Self {
+        self.view_dimension = view_dimension;
+
+        self
+    }
+
+    // Array layers, or the depth of a 3D texture.
+    pub fn set_layer_count(&mut self, layers: u32) -> &mut Self {
+        self.size.depth = layers;
+
+        self
+    }
+
+    pub fn set_component_type(&mut self, component_type: wgpu::TextureComponentType) -> &mut Self {
+        self.component_type = component_type;
+
+        self
+    }
+
+    pub fn set_sample_count(&mut self, sample_count: u32) -> &mut Self {
+        self.sample_count = sample_count;
+
+        self
+    }
+
+    pub fn set_access(&mut self, access: TextureAccess) -> &mut Self {
+        self.access = access;
+        if access != TextureAccess::Sampled {
+            self.usage |= wgpu::TextureUsage::STORAGE;
+        }
+
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), Error> {
+        use wgpu::TextureViewDimension as D;
+
+        let layers = self.size.depth;
+        let is_square = self.size.width == self.size.height;
+        let error = match self.view_dimension {
+            D::D1 if self.size.height != 1 || layers != 1 => {
+                Some("1D textures must have a height and layer count of 1")
+            }
+            D::D2 if layers != 1 => Some("2D textures must have a single layer"),
+            D::Cube if layers != 6 || !is_square => Some("Cube textures need six square layers"),
+            D::CubeArray if layers == 0 || layers % 6 != 0 || !is_square => {
+                Some("Cube array textures need a multiple of six square layers")
+            }
+            _ if layers == 0 => Some("Textures need at least one layer"),
+            _ => None,
+        };
+        let error = error.or_else(|| match self.sample_count {
+            1 => None,
+            _ if self.view_dimension != D::D2 => Some("Only 2D textures can be multisampled"),
+            _ if self.mip_level_count != 1 => Some("Multisampled textures can't have mipmaps"),
+            _ if self.access != TextureAccess::Sampled => {
+                Some("Storage textures can't be multisampled")
+            }
+            _ if self.usage.contains(wgpu::TextureUsage::COPY_DST) => {
+                Some("Multisampled textures can't be written from images")
+            }
+            _ => None,
+        });
+
+        // Depth formats may also be sampled as plain floats.
+        let error = error.or_else(|| {
+            let expected = Self::get_default_component_type(self.format);
+            let is_compatible = self.component_type == expected
+                || (expected == wgpu::TextureComponentType::DepthComparison
+                    && self.component_type == wgpu::TextureComponentType::Float);
+            match self.access {
+                TextureAccess::Sampled if !is_compatible => {
+                    Some("Component type does not match the texture format")
+                }
+                _ => None,
+            }
+        });
+
+        match error {
+            Some(message) => Err(Error::Validation(String::from(message))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn get_format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    pub fn get_mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
+    pub fn get_size(&self) -> wgpu::Extent3d {
+        self.size
+    }
+
+    pub fn get_view_dimension(&self) -> wgpu::TextureViewDimension {
+        self.view_dimension
+    }
+
+    /*-------------------------------------------------*/
+
+    fn get_default_component_type(format: wgpu::TextureFormat) -> wgpu::TextureComponentType {
+        use wgpu::TextureFormat as F;
+        match format {
+            F::Depth32Float | F::Depth24Plus | F::Depth24PlusStencil8 => {
+                wgpu::TextureComponentType::DepthComparison
+            }
+            F::R8Uint
+            | F::R16Uint
+            | F::R32Uint
+            | F::Rg8Uint
+            | F::Rg16Uint
+            | F::Rg32Uint
+            | F::Rgba8Uint
+            | F::Rgba16Uint
+            | F::Rgba32Uint => wgpu::TextureComponentType::Uint,
+            F::R8Sint
+            | F::R16Sint
+            | F::R32Sint
+            | F::Rg8Sint
+            | F::Rg16Sint
+            | F::Rg32Sint
+            | F::Rgba8Sint
+            | F::Rgba16Sint
+            | F::Rgba32Sint => wgpu::TextureComponentType::Sint,
+            _ => wgpu::TextureComponentType::Float,
+        }
+    }
+
+    fn get_texture_dimension(&self) -> wgpu::TextureDimension {
+        get_texture_dimension(self.view_dimension)
+    }
 }
 
 impl BindingLayout<TextureBinding> for TextureBindingLayout {
@@ -32,10 +258,19 @@ impl BindingLayout<TextureBinding> for TextureBindingLayout {
         wgpu::BindGroupLayoutEntry {
             binding: self.binding,
             visibility: self.visibility,
-            ty: wgpu::BindingType::SampledTexture {
-                multisampled: false,
-                component_type: wgpu::TextureComponentType::Float,
-                dimension: wgpu::TextureViewDimension::D2Array,
+            ty: match self.access {
+                TextureAccess::Sampled => wgpu::BindingType::SampledTexture {
+                    multisampled: self.sample_count > 1,
+                    component_type: self.component_type,
+                    dimension: self.view_dimension,
+                },
+                TextureAccess::ReadOnlyStorage | TextureAccess::WriteOnlyStorage => {
+                    wgpu::BindingType::StorageTexture {
+                        dimension: self.view_dimension,
+                        format: self.format,
+                        readonly: self.access == TextureAccess::ReadOnlyStorage,
+                    }
+                }
             },
 
             count: None,
@@ -43,21 +278,32 @@ impl BindingLayout<TextureBinding> for TextureBindingLayout {
     }
 
     fn create_binding(&self, device: &wgpu::Device) -> TextureBinding {
+        self.validate().unwrap_or_else(|err| panic!("{}", err));
+
         let texture = device.create_texture(&wgpu::TextureDescriptor {
             label: None,
             size: self.size,
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
+            mip_level_count: self.mip_level_count,
+            sample_count: self.sample_count,
+            dimension: self.get_texture_dimension(),
 
             format: self.format,
             usage: self.usage,
         });
 
-        let binding_texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let binding_texture_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            dimension: Some(self.view_dimension),
+            ..Default::default()
+        });
         TextureBinding {
             texture,
             binding_texture_view,
+
+            format: self.format,
+            mip_level_count: self.mip_level_count,
+            sample_count: self.sample_count,
+            view_dimension: self.view_dimension,
+            layers: self.size.depth,
         }
     }
 }
@@ -67,6 +313,12 @@ impl BindingLayout<TextureBinding> for TextureBindingLayout {
 pub struct TextureBinding {
     texture: wgpu::Texture,
     binding_texture_view: wgpu::TextureView,
+
+    format: wgpu::TextureFormat,
+    mip_level_count: u32,
+    sample_count: u32,
+    view_dimension: wgpu::TextureViewDimension,
+    layers: u32,
 }
 
 impl Binding for TextureBinding {
@@ -76,16 +328,160 @@ impl Binding for TextureBinding {
 }
 
 impl TextureBinding {
+    pub fn write_image(&self, image: &ImageData, write_queue: &wgpu::Queue) {
+        image.write_to_texture(
+            write_queue,
+            &self.texture,
+            get_texture_dimension(self.view_dimension),
+        );
+    }
+
+    pub fn get_mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+
+    pub fn create_mipmap_target(
+        &self,
+        device: &wgpu::Device,
+        generator: &mut MipmapGenerator,
+    ) -> Result<MipmapTarget, Error> {
+        match self.view_dimension {
+            wgpu::TextureViewDimension::D1 | wgpu::TextureViewDimension::D3 => {
+                return Err(Error::Validation(String::from(
+                    "Mipmaps can only be generated for 2D and cube textures",
+                )))
+            }
+            _ if self.sample_count > 1 => {
+                return Err(Error::Validation(String::from(
+                    "Mipmaps can't be generated for multisampled textures",
+                )))
+            }
+            _ => (),
+        }
+
+        generator.create_target(
+            device,
+            &self.texture,
+            self.format,
+            self.mip_level_count,
+            self.layers,
+        )
+    }
+
+    pub fn get_layer_count(&self) -> u32 {
+        self.layers
+    }
+
+    // Single-level view of the first layer, or of the whole volume for 3D textures.
     pub fn create_texture_view(&self) -> wgpu::TextureView {
+        match self.view_dimension {
+            wgpu::TextureViewDimension::D1 => {
+                self.create_level_view(wgpu::TextureViewDimension::D1, 0)
+            }
+            wgpu::TextureViewDimension::D3 => {
+                self.create_level_view(wgpu::TextureViewDimension::D3, 0)
+            }
+            _ => self.create_level_view(wgpu::TextureViewDimension::D2, 0),
+        }
+    }
+
+    // A single-layer, single-level view to render into. The slices of a 3D texture can't be
+    // viewed on their own.
+    pub fn create_layer_view(&self, layer: u32) -> Result<wgpu::TextureView, Error> {
+        match self.view_dimension {
+            wgpu::TextureViewDimension::D3 => Err(Error::Validation(String::from(
+                "3D textures have no layers to view separately",
+            ))),
+            _ if layer >= self.layers => Err(Error::Validation(format!(
+                "Layer {} is out of range for a texture with {} layers",
+                layer, self.layers
+            ))),
+            wgpu::TextureViewDimension::D1 => {
+                Ok(self.create_level_view(wgpu::TextureViewDimension::D1, layer))
+            }
+            _ => Ok(self.create_level_view(wgpu::TextureViewDimension::D2, layer)),
+        }
+    }
+
+    /*-------------------------------------------------*/
+
+    fn create_level_view(
+        &self,
+        dimension: wgpu::TextureViewDimension,
+        layer: u32,
+    ) -> wgpu::TextureView {
         self.texture.create_view(&wgpu::TextureViewDescriptor {
             label: None,
             format: None,
-            dimension: Some(wgpu::TextureViewDimension::D2),
+            dimension: Some(dimension),
             aspect: wgpu::TextureAspect::All,
             base_mip_level: 0,
-            level_count: None,
-            base_array_layer: 0,
-            array_layer_count: None,
+            level_count: NonZeroU32::new(1),
+            base_array_layer: layer,
+            array_layer_count: NonZeroU32::new(1),
         })
     }
 }
+
+/*--------------------------------------------------------------------------------------------------*/
+
+fn get_texture_dimension(view_dimension: wgpu::TextureViewDimension) -> wgpu::TextureDimension {
+    match view_dimension {
+        wgpu::TextureViewDimension::D1 => wgpu::TextureDimension::D1,
+        wgpu::TextureViewDimension::D3 => wgpu::TextureDimension::D3,
+        _ => wgpu::TextureDimension::D2,
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_layout(format: wgpu::TextureFormat, layers: u32) -> TextureBindingLayout {
+        TextureBindingLayout::new_sampled_output(
+            wgpu::ShaderStage::FRAGMENT,
+            wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth: layers,
+            },
+            format,
+        )
+    }
+
+    #[test]
+    fn validates_component_types() {
+        let mut layout = create_layout(wgpu::TextureFormat::R32Uint, 1);
+        assert!(layout.validate().is_ok());
+        layout.set_component_type(wgpu::TextureComponentType::Float);
+        assert!(layout.validate().is_err());
+
+        let mut layout = create_layout(wgpu::TextureFormat::Depth32Float, 1);
+        assert!(layout.validate().is_ok());
+        layout.set_component_type(wgpu::TextureComponentType::Float);
+        assert!(layout.validate().is_ok());
+        layout.set_component_type(wgpu::TextureComponentType::Sint);
+        assert!(layout.validate().is_err());
+
+        // Storage textures are typed by their format alone.
+        let mut layout = create_layout(wgpu::TextureFormat::R32Uint, 1);
+        layout
+            .set_component_type(wgpu::TextureComponentType::Float)
+            .set_access(TextureAccess::WriteOnlyStorage);
+        assert!(layout.validate().is_ok());
+    }
+
+    #[test]
+    fn validates_layer_counts() {
+        let mut layout = create_layout(wgpu::TextureFormat::Rgba8Unorm, 6);
+        assert!(layout.validate().is_ok());
+        layout.set_view_dimension(wgpu::TextureViewDimension::Cube);
+        assert!(layout.validate().is_ok());
+        layout.set_view_dimension(wgpu::TextureViewDimension::D2);
+        assert!(layout.validate().is_err());
+        layout.set_view_dimension(wgpu::TextureViewDimension::D3);
+        assert!(layout.validate().is_ok());
+    }
+}
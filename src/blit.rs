@@ -0,0 +1,261 @@
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{error::Error, shader::ShaderCompiler};
+
+const BLIT_VERTEX_SHADER: &str = "
+#version 450
+
+layout(location = 0) out vec2 v_uv;
+
+void main() {
+    v_uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    gl_Position = vec4(v_uv * vec2(2.0, -2.0) + vec2(-1.0, 1.0), 0.0, 1.0);
+}
+";
+
+const BLIT_FRAGMENT_SHADER: &str = "
+#version 450
+
+layout(location = 0) in vec2 v_uv;
+layout(location = 0) out vec4 f_color;
+
+layout(set = 0, binding = 0) uniform texture2D t_source;
+layout(set = 0, binding = 1) uniform sampler s_source;
+
+void main() {
+    f_color = texture(sampler2D(t_source, s_source), v_uv);
+}
+";
+
+// Draws a sampled 2D view over a whole render target with a single triangle. Pipelines are
+// created per target format on first use.
+pub struct Blitter {
+    vertex_module: wgpu::ShaderModule,
+    fragment_module: wgpu::ShaderModule,
+
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    sampler: wgpu::Sampler,
+
+    pipelines: HashMap<wgpu::TextureFormat, Rc<wgpu::RenderPipeline>>,
+}
+
+impl Blitter {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self::try_new(device).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(device: &wgpu::Device) -> Result<Self, Error> {
+        let mut shader_compiler = ShaderCompiler::try_new()?;
+        let vertex_module = device.create_shader_module(shader_compiler.try_compile_source(
+            BLIT_VERTEX_SHADER,
+            "blit.vert",
+            shaderc::ShaderKind::Vertex,
+        )?);
+        let fragment_module = device.create_shader_module(shader_compiler.try_compile_source(
+            BLIT_FRAGMENT_SHADER,
+            "blit.frag",
+            shaderc::ShaderKind::Fragment,
+        )?);
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::SampledTexture {
+                        multisampled: false,
+                        component_type: wgpu::TextureComponentType::Float,
+                        dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler { comparison: false },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Minifying to exactly half the size averages each 2x2 texel block, and same size copies
+        // sample texel centers, which linear filtering leaves untouched.
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: None,
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: std::f32::MAX,
+            compare: None,
+            anisotropy_clamp: None,
+        });
+
+        Ok(Self {
+            vertex_module,
+            fragment_module,
+
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+
+            pipelines: HashMap::new(),
+        })
+    }
+
+    // Block-compressed formats can't be rendered to, and 32-bit floats can't be filtered.
+    pub fn supports_format(format: wgpu::TextureFormat) -> bool {
+        use wgpu::TextureFormat as F;
+        matches!(
+            format,
+            F::R8Unorm
+                | F::R16Float
+                | F::Rg8Unorm
+                | F::Rg16Float
+                | F::Rgba8Unorm
+                | F::Rgba8UnormSrgb
+                | F::Bgra8Unorm
+                | F::Bgra8UnormSrgb
+                | F::Rgb10a2Unorm
+                | F::Rg11b10Float
+                | F::Rgba16Float
+        )
+    }
+
+    // Copies a whole single-sampled 2D view into a render target of the given format.
+    pub fn create_blit(
+        &mut self,
+        device: &wgpu::Device,
+        source: &wgpu::TextureView,
+        target_format: wgpu::TextureFormat,
+    ) -> Result<TextureBlit, Error> {
+        if !Self::supports_format(target_format) {
+            return Err(Error::Validation(format!(
+                "Textures can't be blitted to format {:?}",
+                target_format
+            )));
+        }
+
+        let pipeline = Rc::clone(self.get_pipeline(device, target_format));
+        let bind_group = self.create_bind_group(device, source);
+
+        Ok(TextureBlit {
+            pipeline,
+            bind_group,
+        })
+    }
+
+    pub(crate) fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        source_view: &wgpu::TextureView,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    pub(crate) fn get_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+    ) -> &Rc<wgpu::RenderPipeline> {
+        let vertex_module = &self.vertex_module;
+        let fragment_module = &self.fragment_module;
+        let pipeline_layout = &self.pipeline_layout;
+
+        self.pipelines.entry(format).or_insert_with(|| {
+            Rc::new(
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: None,
+                    layout: Some(pipeline_layout),
+                    vertex_stage: wgpu::ProgrammableStageDescriptor {
+                        module: vertex_module,
+                        entry_point: "main",
+                    },
+                    fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+                        module: fragment_module,
+                        entry_point: "main",
+                    }),
+                    rasterization_state: None,
+                    primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+                    color_states: &[wgpu::ColorStateDescriptor {
+                        format,
+                        color_blend: wgpu::BlendDescriptor::REPLACE,
+                        alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                        write_mask: wgpu::ColorWrite::ALL,
+                    }],
+                    depth_stencil_state: None,
+                    vertex_state: wgpu::VertexStateDescriptor {
+                        index_format: wgpu::IndexFormat::Uint16,
+                        vertex_buffers: &[],
+                    },
+                    sample_count: 1,
+                    sample_mask: !0,
+                    alpha_to_coverage_enabled: false,
+                }),
+            )
+        })
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct TextureBlit {
+    pipeline: Rc<wgpu::RenderPipeline>,
+    bind_group: wgpu::BindGroup,
+}
+
+impl TextureBlit {
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder, target_view: &wgpu::TextureView) {
+        encode_blit(encoder, &self.pipeline, &self.bind_group, target_view);
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub(crate) fn encode_blit(
+    encoder: &mut wgpu::CommandEncoder,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+    target_view: &wgpu::TextureView,
+) {
+    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+            attachment: target_view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                store: true,
+            },
+        }],
+        depth_stencil_attachment: None,
+    });
+
+    rpass.set_pipeline(pipeline);
+    rpass.set_bind_group(0, bind_group, &[]);
+    rpass.draw(0..3, 0..1);
+}
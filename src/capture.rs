@@ -0,0 +1,234 @@
+use wgpu;
+
+use crate::error::Error;
+
+pub struct FrameCapture {
+    width: u32,
+    height: u32,
+
+    // Tightly packed RGBA8 rows, top to bottom.
+    pixels: Vec<u8>,
+}
+
+impl FrameCapture {
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, Error> {
+        if pixels.len() as u64 != width as u64 * height as u64 * 4 {
+            return Err(Error::Validation(format!(
+                "{} bytes of pixel data do not match a {}x{} RGBA image",
+                pixels.len(),
+                width,
+                height
+            )));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    // Only 8-bit RGBA and BGRA textures can be read back.
+    pub fn supports_format(format: wgpu::TextureFormat) -> bool {
+        matches!(
+            format,
+            wgpu::TextureFormat::Rgba8Unorm
+                | wgpu::TextureFormat::Rgba8UnormSrgb
+                | wgpu::TextureFormat::Bgra8Unorm
+                | wgpu::TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
+    pub fn read_texture(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        size: wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self, Error> {
+        if !Self::supports_format(format) {
+            return Err(Error::Validation(format!(
+                "Unsupported format for frame capture [{:?}]",
+                format
+            )));
+        }
+
+        let unpadded_bytes_per_row = size.width * 4;
+        let padded_bytes_per_row = Self::get_padded_bytes_per_row(unpadded_bytes_per_row);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (padded_bytes_per_row * size.height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                layout: wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: padded_bytes_per_row,
+                    rows_per_image: size.height,
+                },
+            },
+            wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth: 1,
+            },
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let buffer_slice = buffer.slice(..);
+        let mapping = buffer_slice.map_async(wgpu::MapMode::Read);
+        device.poll(wgpu::Maintain::Wait);
+        futures::executor::block_on(mapping).map_err(|_| {
+            Error::Validation(String::from(
+                "Frame capture buffer could not be mapped, the device may have been lost",
+            ))
+        })?;
+
+        let mut pixels = Vec::<u8>::with_capacity((unpadded_bytes_per_row * size.height) as usize);
+        {
+            let padded_data = buffer_slice.get_mapped_range();
+            for row in padded_data.chunks(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+        buffer.unmap();
+
+        Self::convert_to_rgba(&mut pixels, format);
+        Self::new(size.width, size.height, pixels)
+    }
+
+    pub fn load_png(path: &str) -> Result<Self, Error> {
+        let io_error = |source: std::io::Error| Error::Io {
+            path: String::from(path),
+            source,
+        };
+
+        let decoder = png::Decoder::new(std::fs::File::open(path).map_err(io_error)?);
+        let (info, mut reader) = decoder
+            .read_info()
+            .map_err(|err| io_error(std::io::Error::from(err)))?;
+        if info.color_type != png::ColorType::RGBA || info.bit_depth != png::BitDepth::Eight {
+            return Err(Error::Validation(format!(
+                "Expected an 8-bit RGBA image [{}]",
+                path
+            )));
+        }
+
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader
+            .next_frame(&mut pixels)
+            .map_err(|err| io_error(std::io::Error::from(err)))?;
+
+        Self::new(info.width, info.height, pixels)
+    }
+
+    pub fn save_png(&self, path: &str) -> Result<(), Error> {
+        let io_error = |source: std::io::Error| Error::Io {
+            path: String::from(path),
+            source,
+        };
+
+        let file = std::io::BufWriter::new(std::fs::File::create(path).map_err(io_error)?);
+
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder
+            .write_header()
+            .map_err(|err| io_error(std::io::Error::from(err)))?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|err| io_error(std::io::Error::from(err)))?;
+
+        Ok(())
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * self.width + x) * 4) as usize;
+
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[offset..offset + 4]);
+        pixel
+    }
+
+    /*-------------------------------------------------*/
+
+    fn get_padded_bytes_per_row(unpadded_bytes_per_row: u32) -> u32 {
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padding = (align - unpadded_bytes_per_row % align) % align;
+
+        unpadded_bytes_per_row + padding
+    }
+
+    fn convert_to_rgba(pixels: &mut [u8], format: wgpu::TextureFormat) {
+        if let wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb = format {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_mismatched_pixel_data() {
+        assert!(FrameCapture::new(2, 2, vec![0; 15]).is_err());
+        assert!(FrameCapture::new(2, 2, vec![0; 16]).is_ok());
+    }
+
+    #[test]
+    fn supports_only_8_bit_color_formats() {
+        assert!(FrameCapture::supports_format(
+            wgpu::TextureFormat::Bgra8UnormSrgb
+        ));
+        assert!(FrameCapture::supports_format(
+            wgpu::TextureFormat::Rgba8Unorm
+        ));
+        assert!(!FrameCapture::supports_format(
+            wgpu::TextureFormat::Rgba16Float
+        ));
+        assert!(!FrameCapture::supports_format(
+            wgpu::TextureFormat::Depth32Float
+        ));
+    }
+
+    #[test]
+    fn swaps_bgra_to_rgba() {
+        let mut pixels = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        FrameCapture::convert_to_rgba(&mut pixels, wgpu::TextureFormat::Bgra8Unorm);
+        assert_eq!(pixels, [3, 2, 1, 4, 7, 6, 5, 8]);
+
+        FrameCapture::convert_to_rgba(&mut pixels, wgpu::TextureFormat::Rgba8Unorm);
+        assert_eq!(pixels, [3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+}
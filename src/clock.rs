@@ -0,0 +1,155 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+impl<C: Clock + ?Sized> Clock for Rc<C> {
+    fn now(&self) -> Duration {
+        self.as_ref().now()
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            now: Rc::new(Cell::new(Duration::from_secs(0))),
+        }
+    }
+
+    pub fn advance(&self, delta: Duration) {
+        self.now.set(self.now.get() + delta);
+    }
+
+    pub fn advance_ms(&self, delta: f32) {
+        self.advance(Duration::from_micros((delta * 1000.0) as u64));
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+struct ScaledState {
+    source_time: Duration,
+    scaled_time: Duration,
+
+    scale: f32,
+    paused: bool,
+}
+
+pub struct ScaledClock<C: Clock> {
+    source: Rc<C>,
+    state: Rc<RefCell<ScaledState>>,
+}
+
+impl<C: Clock> Clone for ScaledClock<C> {
+    fn clone(&self) -> Self {
+        Self {
+            source: Rc::clone(&self.source),
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<C: Clock> ScaledClock<C> {
+    pub fn new(source: C) -> Self {
+        let source_time = source.now();
+        Self {
+            source: Rc::new(source),
+            state: Rc::new(RefCell::new(ScaledState {
+                source_time,
+                scaled_time: Duration::from_secs(0),
+
+                scale: 1.0,
+                paused: false,
+            })),
+        }
+    }
+
+    pub fn set_scale(&self, scale: f32) {
+        self.sync();
+        self.state.borrow_mut().scale = scale.max(0.0);
+    }
+
+    pub fn get_scale(&self) -> f32 {
+        self.state.borrow().scale
+    }
+
+    pub fn pause(&self) {
+        self.sync();
+        self.state.borrow_mut().paused = true;
+    }
+
+    pub fn resume(&self) {
+        self.sync();
+        self.state.borrow_mut().paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().paused
+    }
+
+    /*-------------------------------------------------*/
+
+    fn sync(&self) -> Duration {
+        let source_time = self.source.now();
+
+        let mut state = self.state.borrow_mut();
+        let delta = source_time
+            .checked_sub(state.source_time)
+            .unwrap_or_default();
+        state.source_time = source_time;
+
+        if !state.paused {
+            state.scaled_time += delta.mul_f32(state.scale);
+        }
+
+        state.scaled_time
+    }
+}
+
+impl<C: Clock> Clock for ScaledClock<C> {
+    fn now(&self) -> Duration {
+        self.sync()
+    }
+}
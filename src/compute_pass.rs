@@ -0,0 +1,215 @@
+use crate::pipeline::ComputePipeline;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ComputeSchedule {
+    BeforeRendering,
+    AfterRendering,
+    BeforeRenderPass(u32),
+    AfterRenderPass(u32),
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct ComputePass {
+    pipelines: Vec<ComputePipeline>,
+}
+
+impl ComputePass {
+    pub fn new() -> Self {
+        Self {
+            pipelines: Vec::new(),
+        }
+    }
+
+    pub fn add_pipeline(&mut self, pipeline: ComputePipeline) -> u32 {
+        self.pipelines.push(pipeline);
+
+        (self.pipelines.len() - 1) as u32
+    }
+
+    pub fn get_pipeline(&mut self, id: u32) -> &mut ComputePipeline {
+        &mut self.pipelines[id as usize]
+    }
+
+    pub fn refresh_bind_groups(&mut self, device: &wgpu::Device) {
+        self.pipelines
+            .iter_mut()
+            .for_each(|pipeline| pipeline.refresh_bind_groups(device));
+    }
+
+    pub fn submit(&self, encoder: &mut wgpu::CommandEncoder) {
+        let mut cpass = encoder.begin_compute_pass();
+
+        for p in self.pipelines.iter() {
+            p.compute(&mut cpass)
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::GraphicsConfig,
+        graphics::GraphicsManager,
+        handle::{
+            storage::{StorageHandle, StorageHandleLayout},
+            BindingHandleLayout, RcBinding,
+        },
+        pipeline::{BindingLayoutEntries, Vertex},
+        render_pass::{AttachmentView, RenderPass},
+        renderer::Renderer,
+    };
+
+    const COMPUTE_SHADER: &str = "
+#version 450
+
+layout(local_size_x = 1) in;
+
+layout(set = 0, binding = 0) buffer b_Color {
+    vec4 v_Color;
+};
+
+void main() {
+    v_Color = vec4(v_Color.r, 1.0, v_Color.b, 1.0);
+}
+";
+
+    const VERTEX_SHADER: &str = "
+#version 450
+
+layout(location = 0) in vec2 a_Pos;
+
+void main() {
+    gl_Position = vec4(a_Pos, 0.0, 1.0);
+}
+";
+
+    const FRAGMENT_SHADER: &str = "
+#version 450
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 0) readonly buffer b_Color {
+    vec4 v_Color;
+};
+
+void main() {
+    o_Target = v_Color;
+}
+";
+
+    struct ScreenVertex {
+        _position: [f32; 2],
+    }
+
+    impl Vertex for ScreenVertex {
+        fn get_attribute_formats() -> Vec<wgpu::VertexFormat> {
+            vec![wgpu::VertexFormat::Float2]
+        }
+    }
+
+    // Needs a GPU adapter, run it with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn dispatch_runs_before_rendering() {
+        let config = GraphicsConfig::new().with_size(winit::dpi::PhysicalSize::new(4, 4));
+        let mut graphics =
+            futures::executor::block_on(GraphicsManager::try_new_headless(&config)).unwrap();
+
+        let storage_layout =
+            StorageHandleLayout::new::<[f32; 4]>(wgpu::ShaderStage::COMPUTE, 1, false);
+        let mut storage_handle = storage_layout.create_handle(&graphics);
+        assert!(storage_handle.set_data(&[[0.0f32; 2]]).is_err());
+        assert!(storage_handle.set_data(&[[0.0f32; 4]; 2]).is_err());
+        storage_handle.set_data(&[[1.0f32, 0.0, 0.0, 0.0]]).unwrap();
+        let storage: RcBinding<StorageHandle> = RcBinding::new(storage_handle);
+        graphics.update_handle(&storage);
+
+        let mut compute_pipeline = graphics
+            .try_create_compute_pipeline_from_source(
+                COMPUTE_SHADER,
+                BindingLayoutEntries::new().add(&storage_layout),
+            )
+            .unwrap();
+        let dispatch =
+            graphics.add_compute_dispatch(&mut compute_pipeline, vec![storage.share()], [1, 1, 1]);
+        assert!(compute_pipeline.contains_dispatch(dispatch));
+
+        let fragment_layout =
+            StorageHandleLayout::new::<[f32; 4]>(wgpu::ShaderStage::FRAGMENT, 1, true);
+        let mut pipeline = graphics
+            .try_create_pipeline_from_source::<ScreenVertex, u16>(
+                VERTEX_SHADER,
+                FRAGMENT_SHADER,
+                BindingLayoutEntries::new().add(&fragment_layout),
+                wgpu::PrimitiveTopology::TriangleList,
+                Some(wgpu::ColorStateDescriptor {
+                    format: graphics.get_output_format(),
+                    color_blend: wgpu::BlendDescriptor::REPLACE,
+                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                    write_mask: wgpu::ColorWrite::ALL,
+                }),
+                None,
+                None,
+            )
+            .unwrap();
+        let geometry = graphics.create_geometry(
+            vec![
+                ScreenVertex {
+                    _position: [-1.0, -1.0],
+                },
+                ScreenVertex {
+                    _position: [3.0, -1.0],
+                },
+                ScreenVertex {
+                    _position: [-1.0, 3.0],
+                },
+            ],
+            vec![0u16, 1, 2],
+        );
+        graphics
+            .try_add_pipeline_entity(&mut pipeline, &geometry, vec![storage.share()])
+            .unwrap();
+
+        let mut rpass = RenderPass::new();
+        rpass.set_color_attachment(
+            AttachmentView::Dynamic,
+            wgpu::Operations {
+                load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                store: true,
+            },
+        );
+        rpass.add_pipeline(pipeline);
+
+        let mut cpass = ComputePass::new();
+        cpass.add_pipeline(compute_pipeline);
+
+        let mut renderer = Renderer::new();
+        let rpass_id = renderer.add_render_pass(rpass);
+        assert!(renderer
+            .add_compute_pass(
+                ComputePass::new(),
+                ComputeSchedule::AfterRenderPass(rpass_id + 1)
+            )
+            .is_err());
+        renderer
+            .add_compute_pass(cpass, ComputeSchedule::BeforeRenderPass(rpass_id))
+            .unwrap();
+
+        graphics.render(&mut renderer).unwrap();
+        assert_eq!(
+            graphics.capture_frame().unwrap().get_pixel(2, 2),
+            [255, 255, 0, 255]
+        );
+
+        let compute_pipeline = renderer.get_compute_pass(0).get_pipeline(0);
+        compute_pipeline.remove_dispatch(dispatch).unwrap();
+        assert!(!compute_pipeline.contains_dispatch(dispatch));
+        assert!(compute_pipeline
+            .set_workgroups(dispatch, [1, 1, 1])
+            .is_err());
+    }
+}
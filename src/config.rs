@@ -0,0 +1,104 @@
+use wgpu;
+use winit;
+
+#[derive(Clone, Debug)]
+pub struct GraphicsConfig {
+    pub title: String,
+    pub size: Option<winit::dpi::PhysicalSize<u32>>,
+    pub fullscreen: bool,
+
+    pub present_mode: wgpu::PresentMode,
+
+    pub backends: wgpu::BackendBit,
+    pub power_preference: wgpu::PowerPreference,
+
+    pub required_features: wgpu::Features,
+    pub optional_features: wgpu::Features,
+    pub limits: wgpu::Limits,
+    pub shader_validation: bool,
+}
+
+impl GraphicsConfig {
+    pub fn new() -> Self {
+        Self {
+            title: String::from("rustgame"),
+            size: None,
+            fullscreen: false,
+
+            present_mode: wgpu::PresentMode::Mailbox,
+
+            backends: wgpu::BackendBit::PRIMARY,
+            power_preference: wgpu::PowerPreference::HighPerformance,
+
+            required_features: wgpu::Features::empty(),
+            optional_features: wgpu::Features::empty(),
+            limits: wgpu::Limits::default(),
+            shader_validation: true,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = String::from(title);
+        self
+    }
+
+    pub fn with_size(mut self, size: winit::dpi::PhysicalSize<u32>) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.present_mode = if vsync {
+            wgpu::PresentMode::Fifo
+        } else {
+            wgpu::PresentMode::Immediate
+        };
+        self
+    }
+
+    pub fn with_present_mode(mut self, present_mode: wgpu::PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    pub fn with_backends(mut self, backends: wgpu::BackendBit) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    pub fn with_power_preference(mut self, power_preference: wgpu::PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    pub fn with_required_features(mut self, features: wgpu::Features) -> Self {
+        self.required_features = features;
+        self
+    }
+
+    pub fn with_optional_features(mut self, features: wgpu::Features) -> Self {
+        self.optional_features = features;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: wgpu::Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_shader_validation(mut self, shader_validation: bool) -> Self {
+        self.shader_validation = shader_validation;
+        self
+    }
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
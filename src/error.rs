@@ -0,0 +1,95 @@
+use crate::pipeline::EntityId;
+
+#[derive(Debug)]
+pub enum Error {
+    AdapterNotFound,
+    UnsupportedFeatures(wgpu::Features),
+    Device(wgpu::RequestDeviceError),
+    Surface(wgpu::SwapChainError),
+    Window(winit::error::OsError),
+
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    ShaderCompiler,
+    ShaderCompilation {
+        path: String,
+        log: String,
+    },
+    Parse {
+        path: String,
+        line: usize,
+        message: String,
+    },
+    Import {
+        path: String,
+        message: String,
+    },
+
+    Validation(String),
+    StaleEntity(EntityId),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::AdapterNotFound => write!(f, "No compatible adapter found"),
+            Error::UnsupportedFeatures(features) => {
+                write!(
+                    f,
+                    "Adapter does not support required features {:?}",
+                    features
+                )
+            }
+            Error::Device(err) => write!(f, "Device request failed [{}]", err),
+            Error::Surface(err) => write!(f, "Surface error [{}]", err),
+            Error::Window(err) => write!(f, "Window creation failed [{}]", err),
+            Error::Io { path, source } => write!(f, "IO error on [{}]: {}", path, source),
+            Error::ShaderCompiler => write!(f, "Shader compiler could not be initialized"),
+            Error::ShaderCompilation { path, log } => {
+                write!(f, "Shader compilation failed [{}]:\n{}", path, log)
+            }
+            Error::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "Parse error [{}:{}]: {}", path, line, message),
+            Error::Import { path, message } => write!(f, "Import failed [{}]: {}", path, message),
+            Error::Validation(message) => write!(f, "Validation error: {}", message),
+            Error::StaleEntity(id) => write!(f, "Entity {:?} is no longer alive", id),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Device(err) => Some(err),
+            Error::Surface(err) => Some(err),
+            Error::Window(err) => Some(err),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for Error {
+    fn from(err: wgpu::RequestDeviceError) -> Self {
+        Error::Device(err)
+    }
+}
+
+impl From<wgpu::SwapChainError> for Error {
+    fn from(err: wgpu::SwapChainError) -> Self {
+        Error::Surface(err)
+    }
+}
+
+impl From<winit::error::OsError> for Error {
+    fn from(err: winit::error::OsError) -> Self {
+        Error::Window(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,358 @@
+use crate::{
+    capture::FrameCapture, config::GraphicsConfig, error::Error, graphics::GraphicsManager,
+    renderer::Renderer,
+};
+
+const UPDATE_ENV_VAR: &str = "BRICS_UPDATE_GOLDEN";
+
+// Lets test binaries opt into update mode from the environment, as in
+// `golden.set_update(golden::is_update_requested())`.
+pub fn is_update_requested() -> bool {
+    std::env::var_os(UPDATE_ENV_VAR).is_some()
+}
+
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(std::io::Error),
+    Graphics(Error),
+    MissingReference(String),
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    Mismatch {
+        mismatched_pixels: usize,
+        max_difference: u8,
+        diff_path: String,
+    },
+}
+
+impl std::fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::Io(err) => write!(f, "IO error [{}]", err),
+            GoldenError::Graphics(err) => write!(f, "{}", err),
+            GoldenError::MissingReference(path) => write!(
+                f,
+                "Missing reference image [{}], set {}=1 to create it",
+                path, UPDATE_ENV_VAR
+            ),
+            GoldenError::SizeMismatch { expected, actual } => write!(
+                f,
+                "Image size {}x{} does not match reference size {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            GoldenError::Mismatch {
+                mismatched_pixels,
+                max_difference,
+                diff_path,
+            } => write!(
+                f,
+                "{} pixels differ from the reference (max difference {}), see [{}]",
+                mismatched_pixels, max_difference, diff_path
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl From<std::io::Error> for GoldenError {
+    fn from(err: std::io::Error) -> Self {
+        GoldenError::Io(err)
+    }
+}
+
+impl From<Error> for GoldenError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Io { path, source } => GoldenError::Io(std::io::Error::new(
+                source.kind(),
+                format!("{} [{}]", source, path),
+            )),
+            err => GoldenError::Graphics(err),
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct ImageComparison {
+    pub mismatched_pixels: usize,
+    pub max_difference: u8,
+
+    pub diff: FrameCapture,
+}
+
+impl ImageComparison {
+    pub fn new(
+        actual: &FrameCapture,
+        expected: &FrameCapture,
+        tolerance: u8,
+    ) -> Result<Self, GoldenError> {
+        if (expected.get_width(), expected.get_height())
+            != (actual.get_width(), actual.get_height())
+        {
+            return Err(GoldenError::SizeMismatch {
+                expected: (expected.get_width(), expected.get_height()),
+                actual: (actual.get_width(), actual.get_height()),
+            });
+        }
+
+        let mut mismatched_pixels = 0;
+        let mut max_difference = 0;
+
+        let mut diff_pixels = Vec::<u8>::with_capacity(expected.get_pixels().len());
+        for (actual_pixel, expected_pixel) in actual
+            .get_pixels()
+            .chunks(4)
+            .zip(expected.get_pixels().chunks(4))
+        {
+            let difference = actual_pixel
+                .iter()
+                .zip(expected_pixel.iter())
+                .map(|(a, e)| (*a as i16 - *e as i16).abs() as u8)
+                .max()
+                .unwrap_or(0);
+
+            max_difference = max_difference.max(difference);
+            if difference > tolerance {
+                mismatched_pixels += 1;
+                diff_pixels.extend_from_slice(&[255, 0, 0, 255]);
+            } else {
+                let luma = ((expected_pixel[0] as u32
+                    + expected_pixel[1] as u32
+                    + expected_pixel[2] as u32)
+                    / 12) as u8;
+                diff_pixels.extend_from_slice(&[luma, luma, luma, 255]);
+            }
+        }
+
+        Ok(Self {
+            mismatched_pixels,
+            max_difference,
+
+            diff: FrameCapture::new(expected.get_width(), expected.get_height(), diff_pixels)?,
+        })
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct GoldenTest {
+    reference_dir: String,
+    output_dir: String,
+
+    config: GraphicsConfig,
+    tolerance: u8,
+    update: bool,
+}
+
+impl GoldenTest {
+    pub fn new(reference_dir: &str, size: winit::dpi::PhysicalSize<u32>) -> Self {
+        Self {
+            reference_dir: String::from(reference_dir),
+            output_dir: std::env::temp_dir().to_string_lossy().into_owned(),
+
+            config: GraphicsConfig::new().with_size(size),
+            tolerance: 2,
+            update: false,
+        }
+    }
+
+    pub fn set_output_dir(&mut self, output_dir: &str) -> &mut Self {
+        self.output_dir = String::from(output_dir);
+
+        self
+    }
+
+    pub fn set_config(&mut self, config: GraphicsConfig) -> &mut Self {
+        self.config = config;
+
+        self
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: u8) -> &mut Self {
+        self.tolerance = tolerance;
+
+        self
+    }
+
+    // In update mode the compared images overwrite their references instead.
+    pub fn set_update(&mut self, update: bool) -> &mut Self {
+        self.update = update;
+
+        self
+    }
+
+    pub fn check(
+        &self,
+        name: &str,
+        build_scene: impl FnOnce(&GraphicsManager) -> Renderer,
+    ) -> Result<(), GoldenError> {
+        let mut graphics =
+            futures::executor::block_on(GraphicsManager::try_new_headless(&self.config))?;
+        let mut renderer = build_scene(&graphics);
+        graphics.render(&mut renderer)?;
+        let actual = graphics.capture_frame()?;
+
+        self.compare(name, &actual)
+    }
+
+    pub fn compare(&self, name: &str, actual: &FrameCapture) -> Result<(), GoldenError> {
+        let reference_path = format!("{}/{}.png", self.reference_dir, name);
+        if self.update {
+            std::fs::create_dir_all(&self.reference_dir)?;
+            actual.save_png(&reference_path)?;
+            return Ok(());
+        }
+
+        if !std::path::Path::new(&reference_path).exists() {
+            return Err(GoldenError::MissingReference(reference_path));
+        }
+        let expected = FrameCapture::load_png(&reference_path)?;
+
+        let comparison = ImageComparison::new(actual, &expected, self.tolerance)?;
+        if comparison.mismatched_pixels == 0 {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.output_dir)?;
+        let diff_path = format!("{}/{}.diff.png", self.output_dir, name);
+        actual.save_png(&format!("{}/{}.actual.png", self.output_dir, name))?;
+        comparison.diff.save_png(&diff_path)?;
+
+        Err(GoldenError::Mismatch {
+            mismatched_pixels: comparison.mismatched_pixels,
+            max_difference: comparison.max_difference,
+            diff_path,
+        })
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid_image(width: u32, height: u32, pixel: [u8; 4]) -> FrameCapture {
+        let pixels = pixel
+            .iter()
+            .cycle()
+            .take((width * height * 4) as usize)
+            .cloned()
+            .collect();
+
+        FrameCapture::new(width, height, pixels).unwrap()
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let image = solid_image(4, 4, [10, 20, 30, 255]);
+        let comparison = ImageComparison::new(&image, &image, 0).unwrap();
+
+        assert_eq!(comparison.mismatched_pixels, 0);
+        assert_eq!(comparison.max_difference, 0);
+    }
+
+    #[test]
+    fn differences_within_tolerance_match() {
+        let actual = solid_image(4, 4, [12, 20, 28, 255]);
+        let expected = solid_image(4, 4, [10, 20, 30, 255]);
+        let comparison = ImageComparison::new(&actual, &expected, 2).unwrap();
+
+        assert_eq!(comparison.mismatched_pixels, 0);
+        assert_eq!(comparison.max_difference, 2);
+    }
+
+    #[test]
+    fn differences_over_tolerance_are_reported() {
+        let expected = solid_image(2, 2, [10, 20, 30, 255]);
+        let mut pixels = expected.get_pixels().to_vec();
+        pixels[4] = 50;
+        let actual = FrameCapture::new(2, 2, pixels).unwrap();
+        let comparison = ImageComparison::new(&actual, &expected, 2).unwrap();
+
+        assert_eq!(comparison.mismatched_pixels, 1);
+        assert_eq!(comparison.max_difference, 40);
+        assert_eq!(comparison.diff.get_pixel(1, 0), [255, 0, 0, 255]);
+        assert_ne!(comparison.diff.get_pixel(0, 0), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn size_mismatch_is_reported() {
+        let reference_dir = std::env::temp_dir().join("brics_golden_size_mismatch");
+        std::fs::create_dir_all(&reference_dir).unwrap();
+        let reference_dir = reference_dir.to_string_lossy().into_owned();
+
+        solid_image(4, 4, [0, 0, 0, 255])
+            .save_png(&format!("{}/reference.png", reference_dir))
+            .unwrap();
+
+        let golden = GoldenTest::new(&reference_dir, winit::dpi::PhysicalSize::new(4, 4));
+        match golden.compare("reference", &solid_image(2, 4, [0, 0, 0, 255])) {
+            Err(GoldenError::SizeMismatch { expected, actual }) => {
+                assert_eq!(expected, (4, 4));
+                assert_eq!(actual, (2, 4));
+            }
+            result => panic!("Expected a size mismatch, got {:?}", result),
+        }
+    }
+
+    fn create_reference_dir(name: &str) -> String {
+        let reference_dir = std::env::temp_dir().join(format!("brics_golden_{}", name));
+        let _ = std::fs::remove_dir_all(&reference_dir);
+
+        reference_dir.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn missing_reference_is_reported() {
+        let reference_dir = create_reference_dir("missing");
+        let golden = GoldenTest::new(&reference_dir, winit::dpi::PhysicalSize::new(2, 2));
+
+        let result = golden.compare("reference", &solid_image(2, 2, [0, 0, 0, 255]));
+        assert!(matches!(result, Err(GoldenError::MissingReference(_))));
+        assert!(!std::path::Path::new(&reference_dir).exists());
+    }
+
+    #[test]
+    fn update_mode_writes_references() {
+        let reference_dir = create_reference_dir("update");
+        let mut golden = GoldenTest::new(&reference_dir, winit::dpi::PhysicalSize::new(2, 2));
+        golden.set_output_dir(&reference_dir);
+        let image = solid_image(2, 2, [10, 20, 30, 255]);
+
+        golden
+            .set_update(true)
+            .compare("reference", &image)
+            .unwrap();
+        let reference = FrameCapture::load_png(&format!("{}/reference.png", reference_dir));
+        assert_eq!(reference.unwrap().get_pixels(), image.get_pixels());
+
+        let changed = solid_image(2, 2, [90, 20, 30, 255]);
+        assert!(matches!(
+            golden.set_update(false).compare("reference", &changed),
+            Err(GoldenError::Mismatch { .. })
+        ));
+        golden
+            .set_update(true)
+            .compare("reference", &changed)
+            .unwrap();
+        golden
+            .set_update(false)
+            .compare("reference", &changed)
+            .unwrap();
+    }
+
+    #[test]
+    fn png_failures_are_io_errors() {
+        let path = std::env::temp_dir().join("brics_missing_reference.png");
+        let err = FrameCapture::load_png(&path.to_string_lossy())
+            .err()
+            .unwrap();
+
+        assert!(matches!(GoldenError::from(err), GoldenError::Io(_)));
+    }
+}
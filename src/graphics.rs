@@ -1,66 +1,146 @@
-use core::panic;
-
 use wgpu::{self, util::DeviceExt};
 use winit;
 
-use crate::{binding, handle, pipeline, rcmut, renderer::Renderer, shader};
+use crate::{
+    binding,
+    blit::{Blitter, TextureBlit},
+    capture::FrameCapture,
+    config::GraphicsConfig,
+    error::Error,
+    handle::{self, BindingHandle, BindingHandleLayout},
+    image::ImageData,
+    mipmap::{MipmapGenerator, MipmapTarget},
+    offscreen::OffscreenTarget,
+    pipeline, rcmut,
+    render_pass::{AttachmentView, SizedView},
+    renderer::Renderer,
+    shader,
+};
 
 use shaderc;
 
-pub struct GraphicsManager {
-    device: wgpu::Device,
-    queue: wgpu::Queue,
+use std::cell::{RefCell, RefMut};
 
+// Swap chain frames can't be read back, so when capture is enabled the frame is rendered into a
+// retained target and blitted to the swap chain.
+struct FrameCopy {
+    target: OffscreenTarget,
+    blit: TextureBlit,
+}
+
+struct WindowOutput {
     window: winit::window::Window,
     surface: wgpu::Surface,
     swap_chain: wgpu::SwapChain,
+
+    present_mode: wgpu::PresentMode,
+    frame_copy: Option<FrameCopy>,
+}
+
+enum Output {
+    Window(WindowOutput),
+    Offscreen(OffscreenTarget),
+}
+
+pub struct GraphicsManager {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+
+    output: Output,
+
+    blitter: RefCell<Option<Blitter>>,
+    mipmap_generator: RefCell<Option<MipmapGenerator>>,
 }
 
 impl GraphicsManager {
     pub async fn new(
         event_loop: &winit::event_loop::EventLoop<()>,
-        window_size: Option<winit::dpi::PhysicalSize<u32>>,
+        config: &GraphicsConfig,
     ) -> Self {
-        let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
+        Self::try_new(event_loop, config)
+            .await
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
 
-        let mut window_builder = winit::window::WindowBuilder::new().with_title("rustgame");
-        if let Some(wsize) = window_size {
+    pub async fn try_new(
+        event_loop: &winit::event_loop::EventLoop<()>,
+        config: &GraphicsConfig,
+    ) -> Result<Self, Error> {
+        let instance = wgpu::Instance::new(config.backends);
+
+        let mut window_builder = winit::window::WindowBuilder::new().with_title(&config.title);
+        if let Some(wsize) = config.size {
             window_builder = window_builder.with_inner_size(wsize);
         }
-        let window = window_builder.build(event_loop).unwrap();
+        if config.fullscreen {
+            window_builder =
+                window_builder.with_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
+        }
+        let window = window_builder.build(event_loop)?;
 
         let surface = unsafe { instance.create_surface(&window) };
 
-        let adapter: wgpu::Adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .unwrap();
+        let (device, queue) = Self::request_device(&instance, Some(&surface), config).await?;
 
-        let needed_limits = wgpu::Limits::default();
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    features: Self::get_features(&adapter),
-                    limits: needed_limits,
-                    shader_validation: true,
-                },
-                None,
-            )
+        let swap_chain = Self::create_swap_chain(&device, &surface, &window, config.present_mode);
+        Ok(Self {
+            device,
+            queue,
+
+            output: Output::Window(WindowOutput {
+                window,
+                surface,
+                swap_chain,
+
+                present_mode: config.present_mode,
+                frame_copy: None,
+            }),
+
+            blitter: RefCell::new(None),
+            mipmap_generator: RefCell::new(None),
+        })
+    }
+
+    pub async fn from_event_loop(
+        event_loop: Option<&winit::event_loop::EventLoop<()>>,
+        config: &GraphicsConfig,
+    ) -> Self {
+        match event_loop {
+            Some(event_loop) => Self::new(event_loop, config).await,
+            None => Self::new_headless(config).await,
+        }
+    }
+
+    pub async fn new_headless(config: &GraphicsConfig) -> Self {
+        Self::try_new_headless(config)
             .await
-            .unwrap();
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub async fn try_new_headless(config: &GraphicsConfig) -> Result<Self, Error> {
+        let size = config.size.ok_or_else(|| {
+            Error::Validation(String::from("Headless rendering requires an output size"))
+        })?;
+
+        let instance = wgpu::Instance::new(config.backends);
 
-        let swap_chain = Self::create_swap_chain(&device, &surface, &window);
-        Self {
+        let (device, queue) = Self::request_device(&instance, None, config).await?;
+
+        let target = OffscreenTarget::new(
+            &device,
+            size.width,
+            size.height,
+            Self::get_swapchain_color_format(),
+        )?;
+        Ok(Self {
             device,
             queue,
 
-            window,
-            surface,
-            swap_chain,
-        }
+            output: Output::Offscreen(target),
+
+            blitter: RefCell::new(None),
+            mipmap_generator: RefCell::new(None),
+        })
     }
 
     pub fn get_swapchain_color_format() -> wgpu::TextureFormat {
@@ -73,19 +153,142 @@ impl GraphicsManager {
         swapchain_color_format
     }
 
-    pub fn render(&mut self, renderer: &Renderer) {
-        let frame = match self.swap_chain.get_current_frame() {
-            Ok(frame) => frame,
-            Err(_) => panic!("fuck off"),
+    // Format of the color attachment `render` draws into: the swap chain, or the offscreen
+    // target when headless. Pipelines drawing to the output should use it.
+    pub fn get_output_format(&self) -> wgpu::TextureFormat {
+        match &self.output {
+            Output::Window(_) => Self::get_swapchain_color_format(),
+            Output::Offscreen(target) => target.get_format(),
+        }
+    }
+
+    pub fn render(&mut self, renderer: &mut Renderer) -> Result<(), Error> {
+        renderer.refresh_bind_groups(&self.device);
+
+        match &mut self.output {
+            Output::Window(output) => {
+                let frame = match output.swap_chain.get_current_frame() {
+                    Ok(frame) => frame,
+                    Err(wgpu::SwapChainError::Timeout) => return Ok(()),
+                    Err(wgpu::SwapChainError::Lost) | Err(wgpu::SwapChainError::Outdated) => {
+                        let window_size = output.window.inner_size();
+                        if window_size.width == 0 || window_size.height == 0 {
+                            return Ok(());
+                        }
+
+                        output.swap_chain = Self::create_swap_chain(
+                            &self.device,
+                            &output.surface,
+                            &output.window,
+                            output.present_mode,
+                        );
+                        match output.swap_chain.get_current_frame() {
+                            Ok(frame) => frame,
+                            Err(wgpu::SwapChainError::OutOfMemory) => {
+                                return Err(Error::Surface(wgpu::SwapChainError::OutOfMemory))
+                            }
+                            Err(_) => return Ok(()),
+                        }
+                    }
+                    Err(wgpu::SwapChainError::OutOfMemory) => {
+                        return Err(Error::Surface(wgpu::SwapChainError::OutOfMemory))
+                    }
+                };
+
+                match &output.frame_copy {
+                    Some(frame_copy) => {
+                        let mut encoder =
+                            self.device
+                                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                                    label: None,
+                                });
+
+                        renderer.submit(&mut encoder, frame_copy.target.get_view());
+                        frame_copy.blit.encode(&mut encoder, &frame.output.view);
+
+                        self.queue.submit(Some(encoder.finish()));
+                    }
+                    None => Self::submit(&self.device, &self.queue, renderer, &frame.output.view),
+                }
+            }
+            Output::Offscreen(target) => {
+                Self::submit(&self.device, &self.queue, renderer, target.get_view());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>, renderer: &mut Renderer) {
+        if size.width == 0 || size.height == 0 {
+            return;
+        }
+
+        // The blit pipeline already exists once capture has been enabled, so this can't fail.
+        let frame_copy = match &self.output {
+            Output::Window(output) if output.frame_copy.is_some() => Some(
+                self.create_frame_copy(size)
+                    .unwrap_or_else(|err| panic!("{}", err)),
+            ),
+            _ => None,
         };
 
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        match &mut self.output {
+            Output::Window(output) => {
+                output.swap_chain = Self::create_swap_chain(
+                    &self.device,
+                    &output.surface,
+                    &output.window,
+                    output.present_mode,
+                );
+                output.frame_copy = frame_copy;
+            }
+            // The format was already accepted when the target was first created.
+            Output::Offscreen(target) => {
+                *target = OffscreenTarget::new(
+                    &self.device,
+                    size.width,
+                    size.height,
+                    target.get_format(),
+                )
+                .unwrap_or_else(|err| panic!("{}", err));
+            }
+        }
 
-        renderer.submit(&mut encoder, &frame);
+        renderer.resize(&self.device, size);
+    }
 
-        self.queue.submit(Some(encoder.finish()));
+    // Keeps a readable copy of every window frame; offscreen targets are always readable.
+    pub fn set_frame_capture(&mut self, enabled: bool) -> Result<(), Error> {
+        let frame_copy = match &self.output {
+            Output::Window(output) if enabled && output.frame_copy.is_none() => {
+                Some(self.create_frame_copy(output.window.inner_size())?)
+            }
+            _ => None,
+        };
+
+        if let Output::Window(output) = &mut self.output {
+            if !enabled {
+                output.frame_copy = None;
+            } else if frame_copy.is_some() {
+                output.frame_copy = frame_copy;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Reads back the last frame drawn by `render`.
+    pub fn capture_frame(&self) -> Result<FrameCapture, Error> {
+        match &self.output {
+            Output::Window(output) => match &output.frame_copy {
+                Some(frame_copy) => frame_copy.target.capture(&self.device, &self.queue),
+                None => Err(Error::Validation(String::from(
+                    "Frame capture is not enabled for the window output",
+                ))),
+            },
+            Output::Offscreen(target) => target.capture(&self.device, &self.queue),
+        }
     }
 
     pub fn request_redraw(&self) {
@@ -108,19 +311,41 @@ impl GraphicsManager {
         }
 
         #[cfg(not(target_os = "android"))]
-        self.window.request_redraw();
+        if let Output::Window(output) = &self.output {
+            output.window.request_redraw();
+        }
     }
 
-    pub fn get_window(&mut self) -> &mut winit::window::Window {
-        &mut self.window
+    pub fn get_window(&mut self) -> Option<&mut winit::window::Window> {
+        match &mut self.output {
+            Output::Window(output) => Some(&mut output.window),
+            Output::Offscreen(_) => None,
+        }
     }
 
     pub fn get_window_size(&self) -> winit::dpi::PhysicalSize<u32> {
-        self.window.inner_size()
+        match &self.output {
+            Output::Window(output) => output.window.inner_size(),
+            Output::Offscreen(target) => {
+                let size = target.get_size();
+                winit::dpi::PhysicalSize::new(size.width, size.height)
+            }
+        }
+    }
+
+    pub fn get_offscreen_target(&self) -> Option<&OffscreenTarget> {
+        match &self.output {
+            Output::Window(_) => None,
+            Output::Offscreen(target) => Some(target),
+        }
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.get_offscreen_target().is_some()
     }
 
     pub fn update_handle<T: handle::BindingHandle>(&self, binding_handle: &rcmut::RcMut<T>) {
-        binding_handle.update(&self.queue);
+        binding_handle.borrow().update(&self.queue);
     }
 
     pub fn create_binding<B: binding::Binding>(
@@ -130,60 +355,398 @@ impl GraphicsManager {
         binding_layout.create_binding(&self.device)
     }
 
-    pub fn create_geometry<T: pipeline::Vertex>(
+    // Validates the layout instead of panicking in `create_handle`.
+    pub fn try_create_texture(
+        &self,
+        layout: &handle::texture::TextureHandleLayout,
+    ) -> Result<handle::texture::TextureHandle, Error> {
+        layout.get_binding_layout().validate()?;
+
+        Ok(layout.create_handle(self))
+    }
+
+    pub fn create_image_texture(
+        &self,
+        layout: &handle::texture::TextureHandleLayout,
+        mut image: ImageData,
+    ) -> Result<handle::texture::TextureHandle, Error> {
+        if image.is_compressed()
+            && !self
+                .device
+                .features()
+                .contains(wgpu::Features::TEXTURE_COMPRESSION_BC)
+        {
+            return Err(Error::UnsupportedFeatures(
+                wgpu::Features::TEXTURE_COMPRESSION_BC,
+            ));
+        }
+        if layout.get_binding_layout().get_format() != image.get_format() {
+            return Err(Error::Validation(String::from(
+                "Image format does not match the texture layout",
+            )));
+        }
+        if layout.get_binding_layout().get_size() != image.get_size() {
+            return Err(Error::Validation(String::from(
+                "Image size does not match the texture layout",
+            )));
+        }
+        let mip_level_count = layout.get_binding_layout().get_mip_level_count();
+        if image.get_mip_level_count() > mip_level_count {
+            return Err(Error::Validation(String::from(
+                "Image has more mip levels than the texture layout",
+            )));
+        }
+
+        // Checked before anything is created or uploaded.
+        let view_dimension = layout.get_binding_layout().get_view_dimension();
+        let is_missing_levels = image.get_mip_level_count() < mip_level_count;
+        if view_dimension == wgpu::TextureViewDimension::D3
+            && (is_missing_levels || image.get_mip_level_count() > 1)
+        {
+            return Err(Error::Validation(String::from(
+                "Mipmaps of 3D textures can't be generated or loaded from images",
+            )));
+        }
+
+        let generate_on_gpu = is_missing_levels
+            && view_dimension != wgpu::TextureViewDimension::D1
+            && MipmapGenerator::supports_format(image.get_format());
+        if is_missing_levels && !generate_on_gpu {
+            image.generate_mip_levels(mip_level_count)?;
+        }
+
+        let mut texture_handle = self.try_create_texture(layout)?;
+        texture_handle.set_image(image);
+        texture_handle.update(&self.queue);
+
+        if generate_on_gpu {
+            self.generate_mipmaps(&texture_handle)?;
+        }
+
+        Ok(texture_handle)
+    }
+
+    pub fn generate_mipmaps(&self, texture: &handle::texture::TextureHandle) -> Result<(), Error> {
+        let target = self.create_mipmap_target(texture)?;
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        target.encode(&mut encoder);
+        self.queue.submit(Some(encoder.finish()));
+
+        Ok(())
+    }
+
+    pub fn create_mipmap_target(
+        &self,
+        texture: &handle::texture::TextureHandle,
+    ) -> Result<MipmapTarget, Error> {
+        texture.create_mipmap_target(&self.device, &mut *self.get_mipmap_generator()?)
+    }
+
+    pub fn create_geometry<T: pipeline::Vertex, I: pipeline::Index>(
         &self,
         vertices: Vec<T>,
-        indices: Vec<u16>,
+        indices: Vec<I>,
     ) -> pipeline::Geometry {
         let vertex_buffer = self.create_device_buffer(&vertices, wgpu::BufferUsage::VERTEX);
         let index_buffer = self.create_device_buffer(&indices, wgpu::BufferUsage::INDEX);
 
         let n_indices = indices.len() as u32;
 
-        pipeline::Geometry::new(vertex_buffer, index_buffer, n_indices)
+        pipeline::Geometry::new::<I>(vertex_buffer, index_buffer, n_indices)
+    }
+
+    pub fn create_non_indexed_geometry<T: pipeline::Vertex>(
+        &self,
+        vertices: Vec<T>,
+    ) -> pipeline::Geometry {
+        let vertex_buffer = self.create_device_buffer(&vertices, wgpu::BufferUsage::VERTEX);
+
+        pipeline::Geometry::new_non_indexed(vertex_buffer, vertices.len() as u32)
     }
 
     pub fn add_pipeline_entity(
         &self,
         pipeline: &mut pipeline::Pipeline,
         geometry: &pipeline::Geometry,
-        handles: Vec<&dyn handle::BindingHandle>,
-    ) -> u32 {
+        handles: Vec<handle::SharedBindingHandle>,
+    ) -> pipeline::EntityId {
+        self.try_add_pipeline_entity(pipeline, geometry, handles)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_add_pipeline_entity(
+        &self,
+        pipeline: &mut pipeline::Pipeline,
+        geometry: &pipeline::Geometry,
+        handles: Vec<handle::SharedBindingHandle>,
+    ) -> Result<pipeline::EntityId, Error> {
         pipeline.add_entity(&self.device, geometry, handles, 1)
     }
 
-    pub fn create_pipeline<T: pipeline::Vertex>(
+    // The entities using the array pick up a reallocated buffer on the next `render`.
+    pub fn update_instance_array<T>(
+        &self,
+        instance_array: &mut handle::instance_array::InstanceArrayHandle,
+        data: &[T],
+    ) -> Result<(), Error> {
+        instance_array.set_data(&self.device, &self.queue, data)
+    }
+
+    pub fn add_instanced_pipeline_entity<I: pipeline::Vertex + 'static>(
+        &self,
+        pipeline: &mut pipeline::Pipeline,
+        geometry: &pipeline::Geometry,
+        handles: Vec<handle::SharedBindingHandle>,
+        instances: &[I],
+    ) -> Result<pipeline::EntityId, Error> {
+        pipeline.add_instanced_entity(&self.device, &self.queue, geometry, handles, instances)
+    }
+
+    pub fn add_pipeline_instance<I: pipeline::Vertex + 'static>(
+        &self,
+        pipeline: &mut pipeline::Pipeline,
+        id: pipeline::EntityId,
+        instance: I,
+    ) -> Result<u32, Error> {
+        pipeline.add_instance(&self.device, &self.queue, id, instance)
+    }
+
+    pub fn update_pipeline_instance<I: pipeline::Vertex + 'static>(
+        &self,
+        pipeline: &mut pipeline::Pipeline,
+        id: pipeline::EntityId,
+        index: u32,
+        instance: I,
+    ) -> Result<(), Error> {
+        pipeline.update_instance(&self.queue, id, index, instance)
+    }
+
+    pub fn remove_pipeline_instance(
+        &self,
+        pipeline: &mut pipeline::Pipeline,
+        id: pipeline::EntityId,
+        index: u32,
+    ) -> Result<Option<u32>, Error> {
+        pipeline.remove_instance(&self.queue, id, index)
+    }
+
+    pub fn set_pipeline_instances<I: pipeline::Vertex + 'static>(
+        &self,
+        pipeline: &mut pipeline::Pipeline,
+        id: pipeline::EntityId,
+        instances: &[I],
+    ) -> Result<(), Error> {
+        pipeline.set_instances(&self.device, &self.queue, id, instances)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pipeline<T: pipeline::Vertex, I: pipeline::Index>(
         &self,
         vertex_shader_path: &str,
         fragment_shader_path: &str,
         binding_entries: pipeline::BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
 
         color_state: Option<wgpu::ColorStateDescriptor>,
         depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
         rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
     ) -> pipeline::Pipeline {
-        let mut shader_compiler = shader::ShaderCompiler::new();
+        self.try_create_pipeline::<T, I>(
+            vertex_shader_path,
+            fragment_shader_path,
+            binding_entries,
+            primitive_topology,
+            color_state,
+            depth_stencil_state,
+            rasterization_state,
+        )
+        .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_create_pipeline<T: pipeline::Vertex, I: pipeline::Index>(
+        &self,
+        vertex_shader_path: &str,
+        fragment_shader_path: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
+
+        color_state: Option<wgpu::ColorStateDescriptor>,
+        depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
+        rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
+    ) -> Result<pipeline::Pipeline, Error> {
+        let shaders = self.compile_shaders(vertex_shader_path, fragment_shader_path)?;
+
+        Ok(pipeline::Pipeline::new::<T, I>(
+            &self.device,
+            &shaders,
+            &binding_entries,
+            primitive_topology,
+            color_state,
+            depth_stencil_state,
+            rasterization_state,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_create_pipeline_from_source<T: pipeline::Vertex, I: pipeline::Index>(
+        &self,
+        vertex_shader_source: &str,
+        fragment_shader_source: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
+
+        color_state: Option<wgpu::ColorStateDescriptor>,
+        depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
+        rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
+    ) -> Result<pipeline::Pipeline, Error> {
+        let mut shader_compiler = shader::ShaderCompiler::try_new()?;
         let shaders = pipeline::Shaders {
-            vertex_module: self.device.create_shader_module(
-                shader_compiler.compile(vertex_shader_path, shaderc::ShaderKind::Vertex),
-            ),
-            fragment_module: self.device.create_shader_module(
-                shader_compiler.compile(fragment_shader_path, shaderc::ShaderKind::Fragment),
-            ),
+            vertex_module: self
+                .device
+                .create_shader_module(shader_compiler.try_compile_source(
+                    vertex_shader_source,
+                    "vertex shader source",
+                    shaderc::ShaderKind::Vertex,
+                )?),
+            fragment_module: self
+                .device
+                .create_shader_module(shader_compiler.try_compile_source(
+                    fragment_shader_source,
+                    "fragment shader source",
+                    shaderc::ShaderKind::Fragment,
+                )?),
         };
 
-        pipeline::Pipeline::new::<T>(
+        Ok(pipeline::Pipeline::new::<T, I>(
             &self.device,
             &shaders,
             &binding_entries,
+            primitive_topology,
+            color_state,
+            depth_stencil_state,
+            rasterization_state,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_instanced_pipeline<
+        T: pipeline::Vertex,
+        I: pipeline::Index,
+        V: pipeline::Vertex + 'static,
+    >(
+        &self,
+        vertex_shader_path: &str,
+        fragment_shader_path: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
+
+        color_state: Option<wgpu::ColorStateDescriptor>,
+        depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
+        rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
+    ) -> pipeline::Pipeline {
+        self.try_create_instanced_pipeline::<T, I, V>(
+            vertex_shader_path,
+            fragment_shader_path,
+            binding_entries,
+            primitive_topology,
             color_state,
             depth_stencil_state,
             rasterization_state,
         )
+        .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_create_instanced_pipeline<
+        T: pipeline::Vertex,
+        I: pipeline::Index,
+        V: pipeline::Vertex + 'static,
+    >(
+        &self,
+        vertex_shader_path: &str,
+        fragment_shader_path: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
+
+        color_state: Option<wgpu::ColorStateDescriptor>,
+        depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
+        rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
+    ) -> Result<pipeline::Pipeline, Error> {
+        let shaders = self.compile_shaders(vertex_shader_path, fragment_shader_path)?;
+
+        Ok(pipeline::Pipeline::new_instanced::<T, I, V>(
+            &self.device,
+            &shaders,
+            &binding_entries,
+            primitive_topology,
+            color_state,
+            depth_stencil_state,
+            rasterization_state,
+        ))
+    }
+
+    pub fn create_compute_pipeline(
+        &self,
+        compute_shader_path: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+    ) -> pipeline::ComputePipeline {
+        self.try_create_compute_pipeline(compute_shader_path, binding_entries)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_create_compute_pipeline(
+        &self,
+        compute_shader_path: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+    ) -> Result<pipeline::ComputePipeline, Error> {
+        let mut shader_compiler = shader::ShaderCompiler::try_new()?;
+        let compute_module = self.device.create_shader_module(
+            shader_compiler.try_compile(compute_shader_path, shaderc::ShaderKind::Compute)?,
+        );
+
+        Ok(pipeline::ComputePipeline::new(
+            &self.device,
+            &compute_module,
+            &binding_entries,
+        ))
+    }
+
+    pub fn try_create_compute_pipeline_from_source(
+        &self,
+        compute_shader_source: &str,
+        binding_entries: pipeline::BindingLayoutEntries,
+    ) -> Result<pipeline::ComputePipeline, Error> {
+        let mut shader_compiler = shader::ShaderCompiler::try_new()?;
+        let compute_module = self
+            .device
+            .create_shader_module(shader_compiler.try_compile_source(
+                compute_shader_source,
+                "compute shader source",
+                shaderc::ShaderKind::Compute,
+            )?);
+
+        Ok(pipeline::ComputePipeline::new(
+            &self.device,
+            &compute_module,
+            &binding_entries,
+        ))
+    }
+
+    pub fn add_compute_dispatch(
+        &self,
+        pipeline: &mut pipeline::ComputePipeline,
+        handles: Vec<handle::SharedBindingHandle>,
+        workgroups: [u32; 3],
+    ) -> pipeline::EntityId {
+        pipeline.add_dispatch(&self.device, handles, workgroups)
     }
 
     pub fn create_depth_texture_view(&self) -> wgpu::TextureView {
-        let window_size = self.window.inner_size();
+        let window_size = self.get_window_size();
         let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
             size: wgpu::Extent3d {
                 width: window_size.width,
@@ -201,12 +764,36 @@ impl GraphicsManager {
         depth_texture.create_view(&wgpu::TextureViewDescriptor::default())
     }
 
+    pub fn create_depth_attachment_view(&self) -> AttachmentView {
+        AttachmentView::Sized(SizedView::new(
+            &self.device,
+            self.get_window_size(),
+            wgpu::TextureFormat::Depth32Float,
+            wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        ))
+    }
+
     /*-------------------------------------------------*/
 
+    fn submit(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        renderer: &Renderer,
+        target: &wgpu::TextureView,
+    ) {
+        let mut encoder =
+            device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        renderer.submit(&mut encoder, target);
+
+        queue.submit(Some(encoder.finish()));
+    }
+
     pub fn create_swap_chain(
         device: &wgpu::Device,
         surface: &wgpu::Surface,
         window: &winit::window::Window,
+        present_mode: wgpu::PresentMode,
     ) -> wgpu::SwapChain {
         let window_size = window.inner_size();
         let swap_chain_descriptor = wgpu::SwapChainDescriptor {
@@ -214,23 +801,100 @@ impl GraphicsManager {
             format: Self::get_swapchain_color_format(),
             width: window_size.width,
             height: window_size.height,
-            present_mode: wgpu::PresentMode::Mailbox,
+            present_mode,
         };
 
         device.create_swap_chain(&surface, &swap_chain_descriptor)
     }
 
-    fn get_features(adapter: &wgpu::Adapter) -> wgpu::Features {
-        let optional_features = wgpu::Features::empty();
-        let required_features = wgpu::Features::empty();
+    async fn request_device(
+        instance: &wgpu::Instance,
+        compatible_surface: Option<&wgpu::Surface>,
+        config: &GraphicsConfig,
+    ) -> Result<(wgpu::Device, wgpu::Queue), Error> {
+        let adapter: wgpu::Adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: config.power_preference,
+                compatible_surface,
+            })
+            .await
+            .ok_or(Error::AdapterNotFound)?;
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    features: Self::get_features(&adapter, config)?,
+                    limits: config.limits.clone(),
+                    shader_validation: config.shader_validation,
+                },
+                None,
+            )
+            .await?;
+
+        Ok((device, queue))
+    }
+
+    fn get_features(
+        adapter: &wgpu::Adapter,
+        config: &GraphicsConfig,
+    ) -> Result<wgpu::Features, Error> {
+        let optional_features = config.optional_features;
+        let required_features = config.required_features;
         let adapter_features = adapter.features();
-        assert!(
-            adapter_features.contains(required_features),
-            "Adapter does not support required features for this example: {:?}",
-            required_features - adapter_features
-        );
+        if !adapter_features.contains(required_features) {
+            return Err(Error::UnsupportedFeatures(
+                required_features - adapter_features,
+            ));
+        }
+
+        Ok((optional_features & adapter_features) | required_features)
+    }
+
+    fn compile_shaders(
+        &self,
+        vertex_shader_path: &str,
+        fragment_shader_path: &str,
+    ) -> Result<pipeline::Shaders, Error> {
+        let mut shader_compiler = shader::ShaderCompiler::try_new()?;
+
+        Ok(pipeline::Shaders {
+            vertex_module: self.device.create_shader_module(
+                shader_compiler.try_compile(vertex_shader_path, shaderc::ShaderKind::Vertex)?,
+            ),
+            fragment_module: self.device.create_shader_module(
+                shader_compiler.try_compile(fragment_shader_path, shaderc::ShaderKind::Fragment)?,
+            ),
+        })
+    }
+
+    fn create_frame_copy(&self, size: winit::dpi::PhysicalSize<u32>) -> Result<FrameCopy, Error> {
+        let format = Self::get_swapchain_color_format();
+        let target = OffscreenTarget::new(&self.device, size.width, size.height, format)?;
+        let blit = self
+            .get_blitter()?
+            .create_blit(&self.device, target.get_view(), format)?;
+
+        Ok(FrameCopy { target, blit })
+    }
+
+    fn get_blitter(&self) -> Result<RefMut<Blitter>, Error> {
+        let mut blitter = self.blitter.borrow_mut();
+        if blitter.is_none() {
+            *blitter = Some(Blitter::try_new(&self.device)?);
+        }
+
+        Ok(RefMut::map(blitter, |blitter| blitter.as_mut().unwrap()))
+    }
+
+    fn get_mipmap_generator(&self) -> Result<RefMut<MipmapGenerator>, Error> {
+        let mut generator = self.mipmap_generator.borrow_mut();
+        if generator.is_none() {
+            *generator = Some(MipmapGenerator::try_new(&self.device)?);
+        }
 
-        (optional_features & adapter_features) | required_features
+        Ok(RefMut::map(generator, |generator| {
+            generator.as_mut().unwrap()
+        }))
     }
 
     fn create_device_buffer<K>(&self, contents: &Vec<K>, usage: wgpu::BufferUsage) -> wgpu::Buffer {
@@ -56,10 +56,23 @@ impl BindingHandleLayout<UniformBinding, UniformBindingLayout, CameraHandle>
 
 /*--------------------------------------------------------------------------------------------------*/
 
+enum Projection {
+    Perspective {
+        angle: f32,
+        aspect_ratio: f32,
+        near: f32,
+        far: Option<f32>,
+    },
+    Ortho,
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
 pub struct CameraHandle {
     binding: UniformBinding,
 
     projection: Matrix4<f32>,
+    projection_kind: Projection,
 
     eye: Point3<f32>,
     center: Point3<f32>,
@@ -71,6 +84,7 @@ impl CameraHandle {
             binding,
 
             projection: Matrix4::from_scale(1.0),
+            projection_kind: Projection::Ortho,
 
             eye: Point3 {
                 x: 1.0,
@@ -98,7 +112,56 @@ impl CameraHandle {
     }
 
     pub fn set_perspective(&mut self, angle: f32, aspect_ratio: f32) -> &mut Self {
-        self.projection = cgmath::perspective(cgmath::Deg(angle), aspect_ratio, 0.01, 1000.0);
+        self.set_perspective_range(angle, aspect_ratio, 0.01, Some(1000.0))
+    }
+
+    // Without a far plane the projection extends to infinity.
+    pub fn set_perspective_range(
+        &mut self,
+        angle: f32,
+        aspect_ratio: f32,
+        near: f32,
+        far: Option<f32>,
+    ) -> &mut Self {
+        self.projection = match far {
+            Some(far) => cgmath::perspective(cgmath::Deg(angle), aspect_ratio, near, far),
+            None => {
+                let f = 1.0 / (cgmath::Rad::from(cgmath::Deg(angle)).0 / 2.0).tan();
+                #[rustfmt::skip]
+                let projection = Matrix4::new(
+                    f / aspect_ratio, 0.0, 0.0, 0.0,
+                    0.0, f, 0.0, 0.0,
+                    0.0, 0.0, -1.0, -1.0,
+                    0.0, 0.0, -2.0 * near, 0.0,
+                );
+                projection
+            }
+        };
+        self.projection_kind = Projection::Perspective {
+            angle,
+            aspect_ratio,
+            near,
+            far,
+        };
+
+        self
+    }
+
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) -> &mut Self {
+        if let Projection::Perspective {
+            angle, near, far, ..
+        } = self.projection_kind
+        {
+            self.set_perspective_range(angle, aspect_ratio, near, far);
+        }
+
+        self
+    }
+
+    pub fn resize(&mut self, size: winit::dpi::PhysicalSize<u32>) -> &mut Self {
+        if size.width > 0 && size.height > 0 {
+            self.set_aspect_ratio(size.width as f32 / size.height as f32);
+        }
 
         self
     }
@@ -113,6 +176,7 @@ impl CameraHandle {
         far: f32,
     ) -> &mut Self {
         self.projection = cgmath::ortho(left, right, bottom, top, near, far);
+        self.projection_kind = Projection::Ortho;
 
         self
     }
@@ -153,6 +217,10 @@ impl CameraHandle {
         (self.center - self.eye).normalize()
     }
 
+    pub fn get_eye(&self) -> Point3<f32> {
+        self.eye
+    }
+
     pub fn get_center(&self) -> Point3<f32> {
         self.center
     }
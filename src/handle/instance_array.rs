@@ -0,0 +1,80 @@
+use super::{BindingHandle, BindingHandleLayout};
+use crate::{
+    binding::{
+        buffer::{InstanceArrayBinding, InstanceArrayBindingLayout},
+        Binding,
+    },
+    error::Error,
+    graphics::GraphicsManager,
+};
+
+/*--------------------------------------------------------------------------------------------------*/
+
+// Growable array of `T` read by the shader as
+//     buffer Instances { uint count; uint _pad[3]; T items[]; };
+// The 16 byte header holds the number of valid elements, `capacity` only sizes the first buffer.
+pub struct InstanceArrayHandleLayout {
+    binding_layout: InstanceArrayBindingLayout,
+}
+
+impl InstanceArrayHandleLayout {
+    pub fn new<T>(visibility: wgpu::ShaderStage, capacity: u32) -> Self {
+        Self {
+            binding_layout: InstanceArrayBindingLayout::new::<T>(visibility, capacity),
+        }
+    }
+}
+
+impl BindingHandleLayout<InstanceArrayBinding, InstanceArrayBindingLayout, InstanceArrayHandle>
+    for InstanceArrayHandleLayout
+{
+    fn get_binding_layout(&self) -> &InstanceArrayBindingLayout {
+        &self.binding_layout
+    }
+
+    fn create_handle(&self, graphics: &GraphicsManager) -> InstanceArrayHandle {
+        InstanceArrayHandle::new(graphics.create_binding(&self.binding_layout))
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct InstanceArrayHandle {
+    binding: InstanceArrayBinding,
+}
+
+impl InstanceArrayHandle {
+    pub fn new(binding: InstanceArrayBinding) -> Self {
+        Self { binding }
+    }
+
+    // Written right away since growing the array needs the device, see
+    // `GraphicsManager::update_instance_array`. Pipelines rebuild the bind groups of the
+    // entities sharing this handle when the buffer is reallocated.
+    pub fn set_data<T>(
+        &mut self,
+        device: &wgpu::Device,
+        write_queue: &wgpu::Queue,
+        data: &[T],
+    ) -> Result<(), Error> {
+        self.binding.update(device, data, write_queue)?;
+
+        Ok(())
+    }
+
+    pub fn get_len(&self) -> u32 {
+        self.binding.get_len()
+    }
+
+    pub fn get_capacity(&self) -> u32 {
+        self.binding.get_capacity()
+    }
+}
+
+impl BindingHandle for InstanceArrayHandle {
+    fn get_binding(&self) -> &dyn Binding {
+        &self.binding
+    }
+
+    fn update(&self, _: &wgpu::Queue) {}
+}
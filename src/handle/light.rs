@@ -6,16 +6,24 @@ use crate::{
     },
     graphics::GraphicsManager,
 };
-use cgmath::{InnerSpace, Vector3};
+use cgmath::{InnerSpace, Vector3, Zero};
 
 /*--------------------------------------------------------------------------------------------------*/
 
+// Matches the std140 block
+//     uniform Light { vec3 custom; float intensity; vec3 color; float outer_cone_cos;
+//                     vec3 spot_direction; float inner_cone_cos; };
+// where `custom` is the position of point and spot lights or the direction of directional ones,
+// and a zero `spot_direction` marks a light without a cone.
 #[derive(Debug)]
-#[repr(align(16))]
+#[repr(C, align(16))]
 struct LightState {
     custom: Vector3<f32>,
     intensity: f32,
     color: Vector3<f32>,
+    outer_cone_cos: f32,
+    spot_direction: Vector3<f32>,
+    inner_cone_cos: f32,
 }
 
 impl LightState {
@@ -24,6 +32,9 @@ impl LightState {
             custom,
             intensity,
             color,
+            outer_cone_cos: -1.0,
+            spot_direction: Vector3::zero(),
+            inner_cone_cos: -1.0,
         }
     }
 }
@@ -95,10 +106,43 @@ impl LightHandle {
         self.state.custom
     }
 
+    // Restricts the light to a cone around `direction`, angles are in radians from its axis.
+    pub fn set_spot(
+        &mut self,
+        direction: Vector3<f32>,
+        inner_cone_angle: f32,
+        outer_cone_angle: f32,
+    ) -> &mut Self {
+        self.state.spot_direction = direction.normalize();
+        self.state.inner_cone_cos = inner_cone_angle.cos();
+        self.state.outer_cone_cos = outer_cone_angle.max(inner_cone_angle).cos();
+        self
+    }
+
+    pub fn clear_spot(&mut self) -> &mut Self {
+        self.state.spot_direction = Vector3::zero();
+        self.state.inner_cone_cos = -1.0;
+        self.state.outer_cone_cos = -1.0;
+        self
+    }
+
+    pub fn get_spot_direction(&self) -> Option<Vector3<f32>> {
+        if self.state.spot_direction.is_zero() {
+            None
+        } else {
+            Some(self.state.spot_direction)
+        }
+    }
+
     pub fn set_color(&mut self, color: Vector3<f32>) -> &mut Self {
         self.state.color = color;
         self
     }
+
+    pub fn set_intensity(&mut self, intensity: f32) -> &mut Self {
+        self.state.intensity = intensity;
+        self
+    }
 }
 
 impl BindingHandle for LightHandle {
@@ -1,17 +1,32 @@
 pub mod camera;
+pub mod instance_array;
 pub mod light;
 pub mod sampler;
 pub mod shape;
+pub mod storage;
 pub mod texture;
 
-use std::ops::Deref;
-
 use crate::{
     binding::{Binding, BindingLayout},
-    graphics,
+    graphics, rcmut,
 };
 use graphics::GraphicsManager;
 
+use cgmath::Point3;
+
+use std::{cell::RefCell, rc::Rc};
+
+pub type RcBinding<T> = rcmut::RcMut<T>;
+
+// Pipeline entities keep their handles to rebuild bind groups and read sort positions.
+pub type SharedBindingHandle = Rc<RefCell<dyn BindingHandle>>;
+
+impl<T: BindingHandle + 'static> RcBinding<T> {
+    pub fn share(&self) -> SharedBindingHandle {
+        self.to_rc()
+    }
+}
+
 /*----------------------------------------------------------------------------------*/
 
 pub trait BindingHandleLayout<B: Binding, L: BindingLayout<B>, H: BindingHandle> {
@@ -24,4 +39,9 @@ pub trait BindingHandle {
     fn get_binding(&self) -> &dyn Binding;
 
     fn update(&self, write_queue: &wgpu::Queue);
+
+    // World position used to sort the pipeline entities this handle is bound to.
+    fn get_position(&self) -> Option<Point3<f32>> {
+        None
+    }
 }
@@ -29,6 +29,18 @@ impl SamplerHandleLayout {
             ),
         }
     }
+
+    pub fn set_lod_clamp(&mut self, min: f32, max: f32) -> &mut Self {
+        self.binding_layout.set_lod_clamp(min, max);
+
+        self
+    }
+
+    pub fn set_anisotropy_clamp(&mut self, clamp: u8) -> &mut Self {
+        self.binding_layout.set_anisotropy_clamp(clamp);
+
+        self
+    }
 }
 
 impl BindingHandleLayout<SamplerBinding, SamplerBindingLayout, SamplerHandle>
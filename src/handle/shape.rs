@@ -7,7 +7,10 @@ use crate::{
     graphics::GraphicsManager,
 };
 
-use cgmath::{InnerSpace, Matrix4, Quaternion, Rad, Vector3};
+use cgmath::{
+    EuclideanSpace, InnerSpace, Matrix3, Matrix4, One, Point3, Quaternion, Rad, SquareMatrix,
+    Vector3,
+};
 
 use wgpu;
 
@@ -22,6 +25,70 @@ struct ShapeState {
 
 /*--------------------------------------------------------------------------------------------------*/
 
+#[derive(Debug, Copy, Clone)]
+struct Transform {
+    translation: Vector3<f32>,
+    rotation: Quaternion<f32>,
+    scale: Vector3<f32>,
+}
+
+impl Transform {
+    // Only used to blend between ticks; shear and projection can't be represented and are dropped.
+    fn from_matrix(model: Matrix4<f32>) -> Self {
+        let columns = [model.x.truncate(), model.y.truncate(), model.z.truncate()];
+        let mut scale = Vector3::new(
+            columns[0].magnitude(),
+            columns[1].magnitude(),
+            columns[2].magnitude(),
+        );
+
+        let basis = Matrix3::from_cols(columns[0], columns[1], columns[2]);
+        if basis.determinant() < 0.0 {
+            scale.x = -scale.x;
+        }
+
+        let rotation = if scale.x == 0.0 || scale.y == 0.0 || scale.z == 0.0 {
+            Quaternion::one()
+        } else {
+            Quaternion::from(Matrix3::from_cols(
+                columns[0] / scale.x,
+                columns[1] / scale.y,
+                columns[2] / scale.z,
+            ))
+            .normalize()
+        };
+
+        Self {
+            translation: model.w.truncate(),
+            rotation,
+            scale,
+        }
+    }
+
+    fn to_matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.translation)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    fn interpolate(&self, target: &Self, alpha: f32) -> Self {
+        // Take the shorter arc between the two rotations.
+        let target_rotation = if self.rotation.dot(target.rotation) < 0.0 {
+            -target.rotation
+        } else {
+            target.rotation
+        };
+
+        Self {
+            translation: self.translation + (target.translation - self.translation) * alpha,
+            rotation: self.rotation.slerp(target_rotation, alpha),
+            scale: self.scale + (target.scale - self.scale) * alpha,
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
 pub struct ShapeHandleLayout {
     binding_layout: UniformBindingLayout,
 }
@@ -51,6 +118,9 @@ pub struct ShapeHandle {
 
     scale: Vector3<f32>,
     state: ShapeState,
+
+    previous_model: Matrix4<f32>,
+    alpha: f32,
 }
 
 impl ShapeHandle {
@@ -67,9 +137,20 @@ impl ShapeHandle {
                     z: 1.0,
                 },
             },
+
+            previous_model: Matrix4::from_scale(1.0),
+            alpha: 1.0,
         }
     }
 
+    pub fn begin_tick(&mut self) {
+        self.previous_model = self.state.model;
+    }
+
+    pub fn set_interpolation(&mut self, alpha: f32) {
+        self.alpha = alpha;
+    }
+
     pub fn set_model(&mut self, model: Matrix4<f32>) {
         self.state.model =
             model * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z);
@@ -100,6 +181,19 @@ impl ShapeHandle {
         self.state.model = self.state.model
             * Matrix4::from_nonuniform_scale(multiplier.x, multiplier.y, multiplier.z);
     }
+
+    /*-------------------------------------------------*/
+
+    // The model matrix is uploaded as is at the tick boundaries and while it doesn't move.
+    fn get_blended_model(&self) -> Matrix4<f32> {
+        if self.alpha >= 1.0 || self.previous_model == self.state.model {
+            return self.state.model;
+        }
+
+        Transform::from_matrix(self.previous_model)
+            .interpolate(&Transform::from_matrix(self.state.model), self.alpha)
+            .to_matrix()
+    }
 }
 
 impl BindingHandle for ShapeHandle {
@@ -108,6 +202,59 @@ impl BindingHandle for ShapeHandle {
     }
 
     fn update(&self, write_queue: &wgpu::Queue) {
-        self.binding.update(&self.state, write_queue);
+        let blended_state = ShapeState {
+            model: self.get_blended_model(),
+            color: self.state.color,
+        };
+        self.binding.update(&blended_state, write_queue);
+    }
+
+    fn get_position(&self) -> Option<Point3<f32>> {
+        Some(Point3::from_vec(self.state.model.w.truncate()))
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_eq(a: Matrix4<f32>, b: Matrix4<f32>) {
+        let a: &[f32; 16] = a.as_ref();
+        let b: &[f32; 16] = b.as_ref();
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-4, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn transform_round_trips_translation_rotation_scale() {
+        let model = Matrix4::from_translation(Vector3::new(1.0, -2.0, 3.0))
+            * Matrix4::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), Rad(0.7))
+            * Matrix4::from_nonuniform_scale(2.0, 0.5, 3.0);
+
+        assert_matrix_eq(Transform::from_matrix(model).to_matrix(), model);
+    }
+
+    #[test]
+    fn interpolation_endpoints_match_the_ticks() {
+        let previous = Matrix4::from_translation(Vector3::new(1.0, 0.0, 0.0));
+        let current = Matrix4::from_translation(Vector3::new(3.0, 0.0, 0.0))
+            * Matrix4::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), Rad(1.0))
+            * Matrix4::from_scale(2.0);
+        let from = Transform::from_matrix(previous);
+        let to = Transform::from_matrix(current);
+
+        assert_matrix_eq(from.interpolate(&to, 0.0).to_matrix(), previous);
+        assert_matrix_eq(from.interpolate(&to, 1.0).to_matrix(), current);
+
+        let halfway = from.interpolate(&to, 0.5).to_matrix();
+        assert_matrix_eq(
+            halfway,
+            Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0))
+                * Matrix4::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), Rad(0.5))
+                * Matrix4::from_scale(1.5),
+        );
     }
 }
@@ -0,0 +1,90 @@
+use super::{BindingHandle, BindingHandleLayout};
+use crate::{
+    binding::{
+        buffer::{StorageBinding, StorageBindingLayout},
+        Binding,
+    },
+    error::Error,
+    graphics::GraphicsManager,
+};
+
+use std::cell::RefCell;
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct StorageHandleLayout {
+    binding_layout: StorageBindingLayout,
+}
+
+impl StorageHandleLayout {
+    pub fn new<T>(visibility: wgpu::ShaderStage, len: u32, readonly: bool) -> Self {
+        Self {
+            binding_layout: StorageBindingLayout::new::<T>(visibility, len, readonly),
+        }
+    }
+}
+
+impl BindingHandleLayout<StorageBinding, StorageBindingLayout, StorageHandle>
+    for StorageHandleLayout
+{
+    fn get_binding_layout(&self) -> &StorageBindingLayout {
+        &self.binding_layout
+    }
+
+    fn create_handle(&self, graphics: &GraphicsManager) -> StorageHandle {
+        StorageHandle::new(graphics.create_binding(&self.binding_layout))
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct StorageHandle {
+    binding: StorageBinding,
+
+    pending_data: RefCell<Option<Vec<u8>>>,
+}
+
+impl StorageHandle {
+    pub fn new(binding: StorageBinding) -> Self {
+        Self {
+            binding,
+
+            pending_data: RefCell::new(None),
+        }
+    }
+
+    // Replaces the buffer contents from the first element on the next update.
+    pub fn set_data<T>(&mut self, data: &[T]) -> Result<(), Error> {
+        self.binding.validate::<T>(0, data.len())?;
+
+        let raw_data: &[u8] = unsafe {
+            std::slice::from_raw_parts(
+                data.as_ptr() as *const u8,
+                data.len() * std::mem::size_of::<T>(),
+            )
+        };
+        *self.pending_data.get_mut() = Some(raw_data.to_vec());
+
+        Ok(())
+    }
+
+    pub fn get_len(&self) -> u32 {
+        self.binding.get_len()
+    }
+
+    pub fn get_buffer(&self) -> &wgpu::Buffer {
+        self.binding.get_buffer()
+    }
+}
+
+impl BindingHandle for StorageHandle {
+    fn get_binding(&self) -> &dyn Binding {
+        &self.binding
+    }
+
+    fn update(&self, write_queue: &wgpu::Queue) {
+        if let Some(data) = self.pending_data.borrow_mut().take() {
+            self.binding.update_raw(0, &data, write_queue);
+        }
+    }
+}
@@ -1,12 +1,17 @@
 use super::{BindingHandle, BindingHandleLayout};
 use crate::{
     binding::{
-        texture::{TextureBinding, TextureBindingLayout},
+        texture::{TextureAccess, TextureBinding, TextureBindingLayout},
         Binding,
     },
+    error::Error,
     graphics::GraphicsManager,
+    image::ImageData,
+    mipmap::{MipmapGenerator, MipmapTarget},
 };
 
+use std::cell::RefCell;
+
 /*--------------------------------------------------------------------------------------------------*/
 
 pub struct TextureHandleLayout {
@@ -23,6 +28,64 @@ impl TextureHandleLayout {
             binding_layout: TextureBindingLayout::new_sampled_output(visibility, size, format),
         }
     }
+
+    pub fn new_storage(
+        visibility: wgpu::ShaderStage,
+        size: wgpu::Extent3d,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        Self {
+            binding_layout: TextureBindingLayout::new_storage(visibility, size, format),
+        }
+    }
+
+    pub fn from_image(visibility: wgpu::ShaderStage, image: &ImageData) -> Self {
+        Self {
+            binding_layout: TextureBindingLayout::new_sampled(visibility, image),
+        }
+    }
+
+    pub fn from_cube_image(visibility: wgpu::ShaderStage, image: &ImageData) -> Self {
+        Self {
+            binding_layout: TextureBindingLayout::new_sampled_cube(visibility, image),
+        }
+    }
+
+    pub fn enable_mipmaps(&mut self) -> &mut Self {
+        self.binding_layout.enable_mipmaps();
+
+        self
+    }
+
+    pub fn set_view_dimension(&mut self, view_dimension: wgpu::TextureViewDimension) -> &mut Self {
+        self.binding_layout.set_view_dimension(view_dimension);
+
+        self
+    }
+
+    pub fn set_layer_count(&mut self, layers: u32) -> &mut Self {
+        self.binding_layout.set_layer_count(layers);
+
+        self
+    }
+
+    pub fn set_component_type(&mut self, component_type: wgpu::TextureComponentType) -> &mut Self {
+        self.binding_layout.set_component_type(component_type);
+
+        self
+    }
+
+    pub fn set_sample_count(&mut self, sample_count: u32) -> &mut Self {
+        self.binding_layout.set_sample_count(sample_count);
+
+        self
+    }
+
+    pub fn set_access(&mut self, access: TextureAccess) -> &mut Self {
+        self.binding_layout.set_access(access);
+
+        self
+    }
 }
 
 impl BindingHandleLayout<TextureBinding, TextureBindingLayout, TextureHandle>
@@ -41,16 +104,46 @@ impl BindingHandleLayout<TextureBinding, TextureBindingLayout, TextureHandle>
 
 pub struct TextureHandle {
     binding: TextureBinding,
+
+    pending_image: RefCell<Option<ImageData>>,
 }
 
 impl TextureHandle {
     pub fn new(binding: TextureBinding) -> Self {
-        Self { binding }
+        Self {
+            binding,
+
+            pending_image: RefCell::new(None),
+        }
+    }
+
+    pub fn set_image(&mut self, image: ImageData) {
+        *self.pending_image.get_mut() = Some(image);
+    }
+
+    pub fn get_mip_level_count(&self) -> u32 {
+        self.binding.get_mip_level_count()
+    }
+
+    pub fn create_mipmap_target(
+        &self,
+        device: &wgpu::Device,
+        generator: &mut MipmapGenerator,
+    ) -> Result<MipmapTarget, Error> {
+        self.binding.create_mipmap_target(device, generator)
+    }
+
+    pub fn get_layer_count(&self) -> u32 {
+        self.binding.get_layer_count()
     }
 
     pub fn create_texture_view(&self) -> wgpu::TextureView {
         self.binding.create_texture_view()
     }
+
+    pub fn create_layer_view(&self, layer: u32) -> Result<wgpu::TextureView, Error> {
+        self.binding.create_layer_view(layer)
+    }
 }
 
 impl BindingHandle for TextureHandle {
@@ -58,7 +151,9 @@ impl BindingHandle for TextureHandle {
         &self.binding
     }
 
-    fn update(&self, _: &wgpu::Queue) {
-        // TODO
+    fn update(&self, write_queue: &wgpu::Queue) {
+        if let Some(image) = self.pending_image.borrow_mut().take() {
+            self.binding.write_image(&image, write_queue);
+        }
     }
 }
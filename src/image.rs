@@ -0,0 +1,991 @@
+use crate::{error::Error, mipmap};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb,
+    Linear,
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Debug, Clone)]
+pub struct ImageData {
+    width: u32,
+    height: u32,
+    layers: u32,
+
+    format: wgpu::TextureFormat,
+
+    // One entry per mip level, each holding every layer back to back.
+    levels: Vec<Vec<u8>>,
+}
+
+impl ImageData {
+    pub fn new(
+        width: u32,
+        height: u32,
+        layers: u32,
+        format: wgpu::TextureFormat,
+        levels: Vec<Vec<u8>>,
+    ) -> Result<Self, Error> {
+        let image = Self {
+            width,
+            height,
+            layers,
+
+            format,
+            levels,
+        };
+
+        if image.levels.is_empty() {
+            return Err(Error::Validation(String::from("Image has no mip levels")));
+        }
+        for (level, data) in image.levels.iter().enumerate() {
+            let expected = image.get_level_size(level as u32) * layers as usize;
+            if data.len() != expected {
+                return Err(Error::Validation(format!(
+                    "Mip level {} has {} bytes, expected {}",
+                    level,
+                    data.len(),
+                    expected
+                )));
+            }
+        }
+        let whole_block_levels = image.get_whole_block_level_count();
+        if whole_block_levels < image.get_mip_level_count() {
+            return Err(Error::Validation(format!(
+                "Mip level {} of a {:?} image is not made of whole blocks",
+                whole_block_levels, image.format
+            )));
+        }
+
+        Ok(image)
+    }
+
+    pub fn load_png(path: &str, color_space: ColorSpace) -> Result<Self, Error> {
+        let io_error = |source: std::io::Error| Error::Io {
+            path: String::from(path),
+            source,
+        };
+
+        let mut decoder = png::Decoder::new(std::fs::File::open(path).map_err(io_error)?);
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let (info, mut reader) = decoder
+            .read_info()
+            .map_err(|err| io_error(std::io::Error::from(err)))?;
+
+        let mut raw = vec![0; reader.output_buffer_size()];
+        reader
+            .next_frame(&mut raw)
+            .map_err(|err| io_error(std::io::Error::from(err)))?;
+
+        let channels = match info.color_type {
+            png::ColorType::Grayscale => 1,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::RGB => 3,
+            png::ColorType::RGBA => 4,
+            png::ColorType::Indexed => {
+                return Err(Error::Validation(format!(
+                    "Indexed PNG was not expanded [{}]",
+                    path
+                )))
+            }
+        };
+
+        let (format, data) = match info.bit_depth {
+            png::BitDepth::Sixteen => {
+                let samples: Vec<u16> = raw
+                    .chunks_exact(2)
+                    .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+                    .collect();
+                Self::convert_16_bit(&samples, channels, color_space)
+            }
+            _ => Self::convert_8_bit(&raw, channels, color_space),
+        };
+
+        Self::new(info.width, info.height, 1, format, vec![data])
+    }
+
+    // Faces are ordered +X, -X, +Y, -Y, +Z, -Z, matching the cube layer order.
+    pub fn load_cube_png(paths: &[&str; 6], color_space: ColorSpace) -> Result<Self, Error> {
+        let faces = paths
+            .iter()
+            .map(|path| Self::load_png(path, color_space))
+            .collect::<Result<Vec<Self>, Error>>()?;
+
+        let first = &faces[0];
+        for (face, path) in faces.iter().zip(paths.iter()) {
+            if face.width != first.width
+                || face.height != first.height
+                || face.format != first.format
+            {
+                return Err(Error::Import {
+                    path: String::from(*path),
+                    message: String::from("Cube face does not match the size and format of +X"),
+                });
+            }
+        }
+
+        let data = faces
+            .iter()
+            .flat_map(|face| face.levels[0].iter().copied())
+            .collect();
+        let image = Self::new(first.width, first.height, 6, first.format, vec![data])?;
+        if !image.is_cube() {
+            return Err(Error::Import {
+                path: String::from(paths[0]),
+                message: String::from("Cube faces must be square"),
+            });
+        }
+
+        Ok(image)
+    }
+
+    pub fn load_dds(path: &str, color_space: ColorSpace) -> Result<Self, Error> {
+        let io_error = |source: std::io::Error| Error::Io {
+            path: String::from(path),
+            source,
+        };
+
+        let mut file = std::io::BufReader::new(std::fs::File::open(path).map_err(io_error)?);
+        let dds = ddsfile::Dds::read(&mut file).map_err(|err| Error::Import {
+            path: String::from(path),
+            message: err.to_string(),
+        })?;
+
+        let format = Self::get_dds_format(&dds, color_space).ok_or_else(|| Error::Import {
+            path: String::from(path),
+            message: String::from("Unsupported DDS pixel format"),
+        })?;
+        if dds.get_depth() > 1 {
+            return Err(Error::Import {
+                path: String::from(path),
+                message: String::from("Volume DDS textures are not supported"),
+            });
+        }
+
+        let mut image = Self {
+            width: dds.get_width(),
+            height: dds.get_height(),
+            layers: 1,
+
+            format,
+            levels: Vec::new(),
+        };
+        let n_levels = dds.get_num_mipmap_levels().max(1);
+        let level_sizes: Vec<usize> = (0..n_levels)
+            .map(|level| image.get_level_size(level))
+            .collect();
+
+        let (layers, levels) =
+            Self::split_dds_layers(&dds.data, &level_sizes).ok_or_else(|| Error::Import {
+                path: String::from(path),
+                message: String::from("DDS data size does not match its header"),
+            })?;
+        image.layers = layers;
+        image.levels = levels;
+
+        // Chains usually go down to 1x1, but wgpu can only write whole blocks.
+        let whole_block_levels = image.get_whole_block_level_count();
+        if whole_block_levels == 0 {
+            return Err(Error::Import {
+                path: String::from(path),
+                message: String::from("Compressed DDS size is not a multiple of its block size"),
+            });
+        }
+        if whole_block_levels < n_levels {
+            log::warn!(
+                "Dropping {} DDS mip levels smaller than a block [{}]",
+                n_levels - whole_block_levels,
+                path
+            );
+            image.levels.truncate(whole_block_levels as usize);
+        }
+
+        Ok(image)
+    }
+
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get_layers(&self) -> u32 {
+        self.layers
+    }
+
+    pub fn get_format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    pub fn get_mip_level_count(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    pub fn get_level(&self, level: u32) -> &[u8] {
+        &self.levels[level as usize]
+    }
+
+    pub fn get_size(&self) -> wgpu::Extent3d {
+        wgpu::Extent3d {
+            width: self.width,
+            height: self.height,
+            depth: self.layers,
+        }
+    }
+
+    pub fn get_level_extent(&self, level: u32) -> (u32, u32) {
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    // Levels are whole blocks (see `get_whole_block_level_count`), so each copy covers exactly
+    // the mip extent, which is what wgpu 0.6 validates block-compressed copies against. The
+    // layers of a 3D texture are its depth slices, which shrink along with the other axes.
+    pub fn write_to_texture(
+        &self,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+        dimension: wgpu::TextureDimension,
+    ) {
+        let (block_size, block_bytes) = Self::get_block_info(self.format);
+
+        for (level, data) in self.levels.iter().enumerate() {
+            let (width, height) = self.get_level_extent(level as u32);
+            let depth = match dimension {
+                wgpu::TextureDimension::D3 => (self.layers >> level).max(1),
+                _ => self.layers,
+            };
+
+            queue.write_texture(
+                wgpu::TextureCopyView {
+                    texture,
+                    mip_level: level as u32,
+                    origin: wgpu::Origin3d::ZERO,
+                },
+                data,
+                wgpu::TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row: width / block_size * block_bytes,
+                    rows_per_image: height,
+                },
+                wgpu::Extent3d {
+                    width,
+                    height,
+                    depth,
+                },
+            );
+        }
+    }
+
+    pub fn is_cube(&self) -> bool {
+        self.layers == 6 && self.width == self.height
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        Self::get_block_info(self.format).0 > 1
+    }
+
+    pub fn generate_mipmaps(&mut self) -> Result<(), Error> {
+        self.generate_mip_levels(mipmap::get_full_mip_level_count(
+            self.get_size(),
+            wgpu::TextureDimension::D2,
+        ))
+    }
+
+    // Rebuilds `level_count` levels from level 0 with a box filter, up to the full chain,
+    // averaging sRGB texels in linear space. Odd dimensions use 3 weighted taps so that every
+    // source texel contributes equally. Block-compressed formats have to ship their own chain.
+    pub fn generate_mip_levels(&mut self, level_count: u32) -> Result<(), Error> {
+        let encoding = TexelEncoding::from_format(self.format).ok_or_else(|| {
+            Error::Validation(format!(
+                "Mipmaps can't be generated on the CPU for format {:?}",
+                self.format
+            ))
+        })?;
+        let channels = encoding.channels;
+
+        self.levels.truncate(1);
+        let mut source = encoding.decode(&self.levels[0]);
+        let n_levels = level_count.min(mipmap::get_full_mip_level_count(
+            self.get_size(),
+            wgpu::TextureDimension::D2,
+        ));
+        for level in 1..n_levels {
+            let (source_width, source_height) = self.get_level_extent(level - 1);
+            let (width, height) = self.get_level_extent(level);
+            let source_layer_size = (source_width * source_height) as usize * channels;
+
+            let mut values = Vec::with_capacity(source.len() / 4 + channels);
+            for layer in source.chunks_exact(source_layer_size) {
+                let texel = |x: u32, y: u32, channel: usize| {
+                    layer[(y * source_width + x) as usize * channels + channel]
+                };
+
+                for y in 0..height {
+                    let y_taps = Self::get_mip_taps(source_height, height, y);
+                    for x in 0..width {
+                        let x_taps = Self::get_mip_taps(source_width, width, x);
+                        for channel in 0..channels {
+                            let mut sum = 0.0;
+                            for (source_y, y_weight) in y_taps.iter().filter(|tap| tap.1 > 0.0) {
+                                for (source_x, x_weight) in x_taps.iter().filter(|tap| tap.1 > 0.0)
+                                {
+                                    sum +=
+                                        texel(*source_x, *source_y, channel) * x_weight * y_weight;
+                                }
+                            }
+                            values.push(sum);
+                        }
+                    }
+                }
+            }
+
+            self.levels.push(encoding.encode(&values));
+            source = values;
+        }
+
+        Ok(())
+    }
+
+    /*-------------------------------------------------*/
+
+    // Source texels and weights reduced into texel `x` of the next level along one axis. An odd
+    // `source` of 2 * target + 1 texels gives each output 3 taps, weighted so that the outer
+    // texels are shared between neighbours in the right proportion.
+    fn get_mip_taps(source: u32, target: u32, x: u32) -> [(u32, f32); 3] {
+        if source == 1 {
+            [(x, 1.0), (x, 0.0), (x, 0.0)]
+        } else if source % 2 == 0 {
+            [(2 * x, 0.5), (2 * x + 1, 0.5), (2 * x, 0.0)]
+        } else {
+            let n = (2 * target + 1) as f32;
+            [
+                (2 * x, (target - x) as f32 / n),
+                (2 * x + 1, target as f32 / n),
+                (2 * x + 2, (x + 1) as f32 / n),
+            ]
+        }
+    }
+
+    // Number of leading mip levels whose extent is a multiple of the block size. Copies to
+    // smaller compressed levels can't be both block aligned and inside the mip.
+    fn get_whole_block_level_count(&self) -> u32 {
+        let (block_size, _) = Self::get_block_info(self.format);
+
+        (0..self.get_mip_level_count())
+            .take_while(|level| {
+                let (width, height) = self.get_level_extent(*level);
+                width % block_size == 0 && height % block_size == 0
+            })
+            .count() as u32
+    }
+
+    // DDS stores every mip of a layer before moving to the next layer. Returns the layer count
+    // and the data regrouped per level, each holding every layer back to back.
+    fn split_dds_layers(data: &[u8], level_sizes: &[usize]) -> Option<(u32, Vec<Vec<u8>>)> {
+        let layer_size: usize = level_sizes.iter().sum();
+        if layer_size == 0 || data.is_empty() || data.len() % layer_size != 0 {
+            return None;
+        }
+
+        let mut levels: Vec<Vec<u8>> = level_sizes.iter().map(|_| Vec::new()).collect();
+        for layer in data.chunks_exact(layer_size) {
+            let mut offset = 0;
+            for (level, size) in level_sizes.iter().enumerate() {
+                levels[level].extend_from_slice(&layer[offset..offset + size]);
+                offset += size;
+            }
+        }
+
+        Some(((data.len() / layer_size) as u32, levels))
+    }
+
+    fn get_level_size(&self, level: u32) -> usize {
+        let (block_size, block_bytes) = Self::get_block_info(self.format);
+        let (width, height) = self.get_level_extent(level);
+
+        let blocks_wide = (width + block_size - 1) / block_size;
+        let blocks_high = (height + block_size - 1) / block_size;
+        (blocks_wide * blocks_high * block_bytes) as usize
+    }
+
+    // (block edge in texels, bytes per block); uncompressed formats are 1x1 blocks.
+    fn get_block_info(format: wgpu::TextureFormat) -> (u32, u32) {
+        use wgpu::TextureFormat as F;
+        match format {
+            F::Bc1RgbaUnorm | F::Bc1RgbaUnormSrgb | F::Bc4RUnorm | F::Bc4RSnorm => (4, 8),
+            F::Bc2RgbaUnorm
+            | F::Bc2RgbaUnormSrgb
+            | F::Bc3RgbaUnorm
+            | F::Bc3RgbaUnormSrgb
+            | F::Bc5RgUnorm
+            | F::Bc5RgSnorm
+            | F::Bc6hRgbUfloat
+            | F::Bc6hRgbSfloat
+            | F::Bc7RgbaUnorm
+            | F::Bc7RgbaUnormSrgb => (4, 16),
+            F::R8Unorm => (1, 1),
+            F::R16Float => (1, 2),
+            F::Rgba16Float => (1, 8),
+            F::Rgba32Float => (1, 16),
+            _ => (1, 4),
+        }
+    }
+
+    fn convert_8_bit(
+        raw: &[u8],
+        channels: usize,
+        color_space: ColorSpace,
+    ) -> (wgpu::TextureFormat, Vec<u8>) {
+        if channels == 1 && color_space == ColorSpace::Linear {
+            return (wgpu::TextureFormat::R8Unorm, raw.to_vec());
+        }
+
+        let format = match color_space {
+            ColorSpace::Srgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            ColorSpace::Linear => wgpu::TextureFormat::Rgba8Unorm,
+        };
+        let data = raw
+            .chunks_exact(channels)
+            .flat_map(|pixel| Self::expand_to_rgba(pixel, 255))
+            .collect();
+
+        (format, data)
+    }
+
+    // wgpu has no 16-bit normalized formats, so wide samples go through half floats,
+    // decoded to linear first when they are sRGB-encoded.
+    fn convert_16_bit(
+        samples: &[u16],
+        channels: usize,
+        color_space: ColorSpace,
+    ) -> (wgpu::TextureFormat, Vec<u8>) {
+        let decode = |sample: u16, is_alpha: bool| {
+            let value = sample as f32 / 65535.0;
+            if color_space == ColorSpace::Srgb && !is_alpha {
+                Self::srgb_to_linear(value)
+            } else {
+                value
+            }
+        };
+
+        if channels == 1 {
+            let data = samples
+                .iter()
+                .flat_map(|sample| Self::to_f16(decode(*sample, false)).to_le_bytes().to_vec())
+                .collect();
+            return (wgpu::TextureFormat::R16Float, data);
+        }
+
+        let data = samples
+            .chunks_exact(channels)
+            .flat_map(|pixel| Self::expand_to_rgba(pixel, 65535))
+            .enumerate()
+            .flat_map(|(index, sample)| {
+                Self::to_f16(decode(sample, index % 4 == 3))
+                    .to_le_bytes()
+                    .to_vec()
+            })
+            .collect();
+
+        (wgpu::TextureFormat::Rgba16Float, data)
+    }
+
+    fn expand_to_rgba<T: Copy>(pixel: &[T], opaque: T) -> Vec<T> {
+        match pixel.len() {
+            1 => vec![pixel[0], pixel[0], pixel[0], opaque],
+            2 => vec![pixel[0], pixel[0], pixel[0], pixel[1]],
+            3 => vec![pixel[0], pixel[1], pixel[2], opaque],
+            _ => pixel.to_vec(),
+        }
+    }
+
+    fn srgb_to_linear(value: f32) -> f32 {
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    fn linear_to_srgb(value: f32) -> f32 {
+        if value <= 0.003_130_8 {
+            value * 12.92
+        } else {
+            1.055 * value.powf(1.0 / 2.4) - 0.055
+        }
+    }
+
+    // Round-to-nearest conversion; NaNs are not preserved.
+    fn to_f16(value: f32) -> u16 {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32 - 127 + 15;
+        let mantissa = bits & 0x007f_ffff;
+
+        if exponent <= 0 {
+            if exponent < -10 {
+                return sign;
+            }
+            let mantissa = mantissa | 0x0080_0000;
+            let shift = (14 - exponent) as u32;
+            let half = (mantissa + (1 << (shift - 1))) >> shift;
+            return sign | half as u16;
+        }
+        if exponent >= 31 {
+            return sign | 0x7c00;
+        }
+
+        let half = ((exponent as u32) << 10) | (mantissa >> 13);
+        let round = (mantissa >> 12) & 1;
+        sign | (half + round) as u16
+    }
+
+    fn from_f16(half: u16) -> f32 {
+        let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+        let exponent = ((half >> 10) & 0x1f) as i32;
+        let mantissa = (half & 0x03ff) as f32;
+
+        match exponent {
+            0 => sign * mantissa * (2.0f32).powi(-24),
+            31 if mantissa == 0.0 => sign * std::f32::INFINITY,
+            31 => std::f32::NAN,
+            _ => sign * (1.0 + mantissa / 1024.0) * (2.0f32).powi(exponent - 15),
+        }
+    }
+
+    fn get_dds_format(dds: &ddsfile::Dds, color_space: ColorSpace) -> Option<wgpu::TextureFormat> {
+        use ddsfile::{D3DFormat, DxgiFormat};
+        use wgpu::TextureFormat as F;
+
+        let srgb = color_space == ColorSpace::Srgb;
+        let pick = |linear: F, srgb_format: F| if srgb { srgb_format } else { linear };
+
+        if let Some(format) = dds.get_dxgi_format() {
+            return Some(match format {
+                DxgiFormat::BC1_UNorm => F::Bc1RgbaUnorm,
+                DxgiFormat::BC1_UNorm_sRGB => F::Bc1RgbaUnormSrgb,
+                DxgiFormat::BC2_UNorm => F::Bc2RgbaUnorm,
+                DxgiFormat::BC2_UNorm_sRGB => F::Bc2RgbaUnormSrgb,
+                DxgiFormat::BC3_UNorm => F::Bc3RgbaUnorm,
+                DxgiFormat::BC3_UNorm_sRGB => F::Bc3RgbaUnormSrgb,
+                DxgiFormat::BC4_UNorm => F::Bc4RUnorm,
+                DxgiFormat::BC4_SNorm => F::Bc4RSnorm,
+                DxgiFormat::BC5_UNorm => F::Bc5RgUnorm,
+                DxgiFormat::BC5_SNorm => F::Bc5RgSnorm,
+                DxgiFormat::BC6H_UF16 => F::Bc6hRgbUfloat,
+                DxgiFormat::BC6H_SF16 => F::Bc6hRgbSfloat,
+                DxgiFormat::BC7_UNorm => F::Bc7RgbaUnorm,
+                DxgiFormat::BC7_UNorm_sRGB => F::Bc7RgbaUnormSrgb,
+                DxgiFormat::R8G8B8A8_UNorm => F::Rgba8Unorm,
+                DxgiFormat::R8G8B8A8_UNorm_sRGB => F::Rgba8UnormSrgb,
+                DxgiFormat::B8G8R8A8_UNorm => F::Bgra8Unorm,
+                DxgiFormat::B8G8R8A8_UNorm_sRGB => F::Bgra8UnormSrgb,
+                DxgiFormat::R16G16B16A16_Float => F::Rgba16Float,
+                DxgiFormat::R32G32B32A32_Float => F::Rgba32Float,
+                DxgiFormat::R8_UNorm => F::R8Unorm,
+                _ => return None,
+            });
+        }
+
+        match dds.get_d3d_format()? {
+            D3DFormat::DXT1 => Some(pick(F::Bc1RgbaUnorm, F::Bc1RgbaUnormSrgb)),
+            D3DFormat::DXT3 => Some(pick(F::Bc2RgbaUnorm, F::Bc2RgbaUnormSrgb)),
+            D3DFormat::DXT5 => Some(pick(F::Bc3RgbaUnorm, F::Bc3RgbaUnormSrgb)),
+            D3DFormat::A8R8G8B8 => Some(pick(F::Bgra8Unorm, F::Bgra8UnormSrgb)),
+            D3DFormat::A8B8G8R8 => Some(pick(F::Rgba8Unorm, F::Rgba8UnormSrgb)),
+            _ => None,
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Copy, Clone)]
+enum TexelKind {
+    Unorm8,
+    Float16,
+    Float32,
+}
+
+#[derive(Copy, Clone)]
+struct TexelEncoding {
+    kind: TexelKind,
+    channels: usize,
+    srgb: bool,
+}
+
+impl TexelEncoding {
+    fn from_format(format: wgpu::TextureFormat) -> Option<Self> {
+        use wgpu::TextureFormat as F;
+
+        let (kind, channels, srgb) = match format {
+            F::R8Unorm => (TexelKind::Unorm8, 1, false),
+            F::Rg8Unorm => (TexelKind::Unorm8, 2, false),
+            F::Rgba8Unorm | F::Bgra8Unorm => (TexelKind::Unorm8, 4, false),
+            F::Rgba8UnormSrgb | F::Bgra8UnormSrgb => (TexelKind::Unorm8, 4, true),
+            F::R16Float => (TexelKind::Float16, 1, false),
+            F::Rg16Float => (TexelKind::Float16, 2, false),
+            F::Rgba16Float => (TexelKind::Float16, 4, false),
+            F::R32Float => (TexelKind::Float32, 1, false),
+            F::Rg32Float => (TexelKind::Float32, 2, false),
+            F::Rgba32Float => (TexelKind::Float32, 4, false),
+            _ => return None,
+        };
+
+        Some(Self {
+            kind,
+            channels,
+            srgb,
+        })
+    }
+
+    fn decode(&self, data: &[u8]) -> Vec<f32> {
+        let values: Vec<f32> = match self.kind {
+            TexelKind::Unorm8 => data.iter().map(|byte| *byte as f32 / 255.0).collect(),
+            TexelKind::Float16 => data
+                .chunks_exact(2)
+                .map(|bytes| ImageData::from_f16(u16::from_le_bytes([bytes[0], bytes[1]])))
+                .collect(),
+            TexelKind::Float32 => data
+                .chunks_exact(4)
+                .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                .collect(),
+        };
+
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                if self.is_srgb_channel(index) {
+                    ImageData::srgb_to_linear(value)
+                } else {
+                    value
+                }
+            })
+            .collect()
+    }
+
+    fn encode(&self, values: &[f32]) -> Vec<u8> {
+        let encoded = values.iter().enumerate().map(|(index, value)| {
+            if self.is_srgb_channel(index) {
+                ImageData::linear_to_srgb(*value)
+            } else {
+                *value
+            }
+        });
+
+        match self.kind {
+            TexelKind::Unorm8 => encoded
+                .map(|value| (value.max(0.0).min(1.0) * 255.0).round() as u8)
+                .collect(),
+            TexelKind::Float16 => encoded
+                .flat_map(|value| ImageData::to_f16(value).to_le_bytes().to_vec())
+                .collect(),
+            TexelKind::Float32 => encoded
+                .flat_map(|value| value.to_le_bytes().to_vec())
+                .collect(),
+        }
+    }
+
+    fn is_srgb_channel(&self, index: usize) -> bool {
+        self.srgb && index % self.channels != 3
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_f16(data: &[u8]) -> Vec<f32> {
+        data.chunks_exact(2)
+            .map(|bytes| ImageData::from_f16(u16::from_le_bytes([bytes[0], bytes[1]])))
+            .collect()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!((a - e).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn converts_to_f16() {
+        assert_eq!(ImageData::to_f16(0.0), 0x0000);
+        assert_eq!(ImageData::to_f16(-0.0), 0x8000);
+        assert_eq!(ImageData::to_f16(1.0), 0x3c00);
+        assert_eq!(ImageData::to_f16(0.5), 0x3800);
+        assert_eq!(ImageData::to_f16(-2.0), 0xc000);
+        assert_eq!(ImageData::to_f16(1.0 / 3.0), 0x3555);
+        assert_eq!(ImageData::to_f16(65504.0), 0x7bff);
+        // Overflow saturates to infinity, tiny values flush to subnormals or zero.
+        assert_eq!(ImageData::to_f16(1.0e6), 0x7c00);
+        assert_eq!(ImageData::to_f16(-1.0e6), 0xfc00);
+        assert_eq!(ImageData::to_f16((2.0f32).powi(-24)), 0x0001);
+        assert_eq!(ImageData::to_f16((2.0f32).powi(-14)), 0x0400);
+        assert_eq!(ImageData::to_f16(1.0e-10), 0x0000);
+    }
+
+    #[test]
+    fn f16_round_trips() {
+        for half in [0x0001_u16, 0x03ff, 0x0400, 0x3555, 0x3c00, 0x7bff, 0xc000].iter() {
+            assert_eq!(ImageData::to_f16(ImageData::from_f16(*half)), *half);
+        }
+        for value in [0.1_f32, 0.25, 0.7, 3.25, 1000.0, -42.5].iter() {
+            let round_trip = ImageData::from_f16(ImageData::to_f16(*value));
+            assert!((round_trip - value).abs() <= value.abs() / 1024.0);
+        }
+        assert_eq!(ImageData::from_f16(0x7c00), std::f32::INFINITY);
+        assert!(ImageData::from_f16(0x7e00).is_nan());
+    }
+
+    #[test]
+    fn converts_8_bit_pngs() {
+        let (format, data) = ImageData::convert_8_bit(&[10, 20], 1, ColorSpace::Linear);
+        assert_eq!(format, wgpu::TextureFormat::R8Unorm);
+        assert_eq!(data, vec![10, 20]);
+
+        let (format, data) = ImageData::convert_8_bit(&[10, 20], 1, ColorSpace::Srgb);
+        assert_eq!(format, wgpu::TextureFormat::Rgba8UnormSrgb);
+        assert_eq!(data, vec![10, 10, 10, 255, 20, 20, 20, 255]);
+
+        let (format, data) = ImageData::convert_8_bit(&[10, 128], 2, ColorSpace::Srgb);
+        assert_eq!(format, wgpu::TextureFormat::Rgba8UnormSrgb);
+        assert_eq!(data, vec![10, 10, 10, 128]);
+
+        let (format, data) = ImageData::convert_8_bit(&[1, 2, 3], 3, ColorSpace::Linear);
+        assert_eq!(format, wgpu::TextureFormat::Rgba8Unorm);
+        assert_eq!(data, vec![1, 2, 3, 255]);
+
+        let (_, data) = ImageData::convert_8_bit(&[1, 2, 3, 4], 4, ColorSpace::Linear);
+        assert_eq!(data, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn converts_16_bit_pngs() {
+        let (format, data) = ImageData::convert_16_bit(&[0, 32768, 65535], 1, ColorSpace::Linear);
+        assert_eq!(format, wgpu::TextureFormat::R16Float);
+        assert_close(&decode_f16(&data), &[0.0, 0.5, 1.0]);
+
+        // sRGB color channels are linearized, alpha is not.
+        let (format, data) = ImageData::convert_16_bit(&[32768, 32768], 2, ColorSpace::Srgb);
+        assert_eq!(format, wgpu::TextureFormat::Rgba16Float);
+        let linear = ImageData::srgb_to_linear(32768.0 / 65535.0);
+        assert_close(&decode_f16(&data), &[linear, linear, linear, 0.5]);
+
+        let (format, data) = ImageData::convert_16_bit(&[0, 65535, 32768], 3, ColorSpace::Linear);
+        assert_eq!(format, wgpu::TextureFormat::Rgba16Float);
+        assert_close(&decode_f16(&data), &[0.0, 1.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn splits_dds_layers_per_level() {
+        // Two layers of a 4x4 R8 image with its 2x2 and 1x1 mips.
+        let level_sizes = [16, 4, 1];
+        let data: Vec<u8> = (0..42).collect();
+
+        let (layers, levels) = ImageData::split_dds_layers(&data, &level_sizes).unwrap();
+        assert_eq!(layers, 2);
+        assert_eq!(levels.len(), 3);
+        assert_eq!(levels[0], [&data[0..16], &data[21..37]].concat());
+        assert_eq!(levels[1], [&data[16..20], &data[37..41]].concat());
+        assert_eq!(levels[2], vec![20, 41]);
+
+        assert!(ImageData::split_dds_layers(&data[..41], &level_sizes).is_none());
+        assert!(ImageData::split_dds_layers(&[], &level_sizes).is_none());
+        assert!(ImageData::split_dds_layers(&data, &[]).is_none());
+    }
+
+    #[test]
+    fn requires_whole_compressed_blocks() {
+        let format = wgpu::TextureFormat::Bc1RgbaUnorm;
+
+        // 8x8 and 4x4 are whole 4x4 blocks of 8 bytes.
+        let image = ImageData::new(8, 8, 1, format, vec![vec![0; 32], vec![0; 8]]).unwrap();
+        assert_eq!(image.get_whole_block_level_count(), 2);
+        assert!(image.is_compressed());
+
+        // The 2x2 level would be stored as one block but can't be copied.
+        assert!(
+            ImageData::new(8, 8, 1, format, vec![vec![0; 32], vec![0; 8], vec![0; 8]]).is_err()
+        );
+        assert!(ImageData::new(6, 6, 1, format, vec![vec![0; 32]]).is_err());
+
+        let image =
+            ImageData::new(3, 3, 2, wgpu::TextureFormat::R8Unorm, vec![vec![0; 18]]).unwrap();
+        assert_eq!(image.get_whole_block_level_count(), 1);
+    }
+
+    #[test]
+    fn generates_box_filtered_mipmaps() {
+        let texels: Vec<u8> = (0..16).map(|index| 4 * index).collect();
+        let mut image =
+            ImageData::new(4, 4, 1, wgpu::TextureFormat::R8Unorm, vec![texels]).unwrap();
+
+        image.generate_mipmaps().unwrap();
+        assert_eq!(image.get_mip_level_count(), 3);
+        assert_eq!(image.get_level(1), &[10, 18, 42, 50]);
+        assert_eq!(image.get_level(2), &[30]);
+    }
+
+    #[test]
+    fn generates_requested_mip_levels() {
+        let mut image =
+            ImageData::new(8, 8, 1, wgpu::TextureFormat::R8Unorm, vec![vec![0; 64]]).unwrap();
+
+        image.generate_mip_levels(2).unwrap();
+        assert_eq!(image.get_mip_level_count(), 2);
+        image.generate_mip_levels(10).unwrap();
+        assert_eq!(image.get_mip_level_count(), 4);
+    }
+
+    #[test]
+    fn generates_srgb_mipmaps_in_linear_space() {
+        let texels = vec![
+            0, 0, 0, 0, 255, 255, 255, 255, //
+            255, 255, 255, 255, 0, 0, 0, 0,
+        ];
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let mut image = ImageData::new(2, 2, 1, format, vec![texels]).unwrap();
+
+        image.generate_mipmaps().unwrap();
+        let gray = (ImageData::linear_to_srgb(0.5) * 255.0).round() as u8;
+        assert!(gray > 128);
+        assert_eq!(image.get_level(1), &[gray, gray, gray, 128]);
+    }
+
+    #[test]
+    fn weighs_odd_mipmap_footprints() {
+        let texels = vec![0, 50, 100, 150, 200];
+        let mut image =
+            ImageData::new(5, 1, 1, wgpu::TextureFormat::R8Unorm, vec![texels]).unwrap();
+
+        image.generate_mipmaps().unwrap();
+        assert_eq!(image.get_level(1), &[40, 160]);
+        assert_eq!(image.get_level(2), &[100]);
+
+        let texels = vec![30, 60, 90, 0, 0, 0];
+        let mut image =
+            ImageData::new(3, 1, 2, wgpu::TextureFormat::R8Unorm, vec![texels]).unwrap();
+        image.generate_mipmaps().unwrap();
+        assert_eq!(image.get_level(1), &[60, 0]);
+    }
+
+    #[test]
+    fn converts_linear_to_srgb() {
+        assert_eq!(ImageData::linear_to_srgb(0.0), 0.0);
+        assert!((ImageData::linear_to_srgb(1.0) - 1.0).abs() < 1e-6);
+        assert!((ImageData::linear_to_srgb(0.5) - 0.735_357).abs() < 1e-5);
+        // Both pieces meet at the threshold.
+        assert!((ImageData::linear_to_srgb(0.003_130_8) - 0.040_45).abs() < 1e-4);
+        for value in [0.001_f32, 0.02, 0.2, 0.5, 0.9].iter() {
+            let round_trip = ImageData::srgb_to_linear(ImageData::linear_to_srgb(*value));
+            assert!((round_trip - value).abs() < 1e-5);
+        }
+    }
+
+    fn write_png(path: &str, width: u32, height: u32, depth: png::BitDepth, data: &[u8]) {
+        let file = std::io::BufWriter::new(std::fs::File::create(path).unwrap());
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(depth);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+    }
+
+    // Writes six RGBA8 faces filled with ten times their index, `change` writes the -Y face.
+    fn write_cube_faces(
+        name: &str,
+        width: u32,
+        height: u32,
+        change: impl Fn(&str, &[u8]),
+    ) -> Vec<String> {
+        let dir = std::env::temp_dir().join(format!("brics_cube_{}", name));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        (0..6)
+            .map(|face| {
+                let path = dir
+                    .join(format!("face_{}.png", face))
+                    .to_string_lossy()
+                    .into_owned();
+                let data = vec![face as u8 * 10; (width * height * 4) as usize];
+                if face == 3 {
+                    change(&path, &data);
+                } else {
+                    write_png(&path, width, height, png::BitDepth::Eight, &data);
+                }
+
+                path
+            })
+            .collect()
+    }
+
+    fn load_cube(paths: &[String]) -> Result<ImageData, Error> {
+        let faces = [
+            paths[0].as_str(),
+            paths[1].as_str(),
+            paths[2].as_str(),
+            paths[3].as_str(),
+            paths[4].as_str(),
+            paths[5].as_str(),
+        ];
+
+        ImageData::load_cube_png(&faces, ColorSpace::Linear)
+    }
+
+    #[test]
+    fn loads_cube_faces_in_layer_order() {
+        let paths = write_cube_faces("order", 2, 2, |path, data| {
+            write_png(path, 2, 2, png::BitDepth::Eight, data)
+        });
+
+        let image = load_cube(&paths).unwrap();
+        assert!(image.is_cube());
+        assert_eq!(image.get_format(), wgpu::TextureFormat::Rgba8Unorm);
+        assert_eq!(image.get_layers(), 6);
+        for (face, texels) in image.get_level(0).chunks_exact(2 * 2 * 4).enumerate() {
+            assert!(texels.iter().all(|texel| *texel == face as u8 * 10));
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_cube_faces() {
+        let paths = write_cube_faces("size", 2, 2, |path, _| {
+            write_png(path, 4, 4, png::BitDepth::Eight, &[0; 4 * 4 * 4])
+        });
+        match load_cube(&paths) {
+            Err(Error::Import { path, .. }) => assert_eq!(path, paths[3]),
+            _ => panic!("expected a size mismatch"),
+        }
+
+        let paths = write_cube_faces("format", 2, 2, |path, _| {
+            write_png(path, 2, 2, png::BitDepth::Sixteen, &[0; 2 * 2 * 8])
+        });
+        match load_cube(&paths) {
+            Err(Error::Import { path, .. }) => assert_eq!(path, paths[3]),
+            _ => panic!("expected a format mismatch"),
+        }
+    }
+
+    #[test]
+    fn rejects_non_square_cube_faces() {
+        let paths = write_cube_faces("non_square", 2, 4, |path, data| {
+            write_png(path, 2, 4, png::BitDepth::Eight, data)
+        });
+
+        match load_cube(&paths) {
+            Err(Error::Import { message, .. }) => assert!(message.contains("square")),
+            _ => panic!("expected non-square faces to be rejected"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use winit::dpi::PhysicalPosition;
 use winit::event::{
-    ElementState, KeyboardInput, MouseButton, Touch, TouchPhase, VirtualKeyCode, WindowEvent,
+    DeviceId, ElementState, KeyboardInput, ModifiersState, MouseButton, Touch, TouchPhase,
+    VirtualKeyCode, WindowEvent,
 };
 
 pub struct InputState {
@@ -130,3 +131,37 @@ pub struct Finger {
     pub id: u64,
     pub location: PhysicalPosition<f64>,
 }
+
+/* ---------------------------- SYNTHETIC ---------------------------- */
+pub fn synthetic_key(key: VirtualKeyCode, state: ElementState) -> WindowEvent<'static> {
+    #[allow(deprecated)]
+    WindowEvent::KeyboardInput {
+        device_id: unsafe { DeviceId::dummy() },
+        input: KeyboardInput {
+            scancode: 0,
+            state,
+            virtual_keycode: Some(key),
+            modifiers: ModifiersState::empty(),
+        },
+        is_synthetic: true,
+    }
+}
+
+pub fn synthetic_mouse_button(button: MouseButton, state: ElementState) -> WindowEvent<'static> {
+    #[allow(deprecated)]
+    WindowEvent::MouseInput {
+        device_id: unsafe { DeviceId::dummy() },
+        state,
+        button,
+        modifiers: ModifiersState::empty(),
+    }
+}
+
+pub fn synthetic_cursor_moved(position: PhysicalPosition<f64>) -> WindowEvent<'static> {
+    #[allow(deprecated)]
+    WindowEvent::CursorMoved {
+        device_id: unsafe { DeviceId::dummy() },
+        position,
+        modifiers: ModifiersState::empty(),
+    }
+}
@@ -1,12 +1,27 @@
 pub mod application;
 pub mod binding;
+pub mod blit;
+pub mod capture;
+pub mod clock;
+pub mod compute_pass;
+pub mod config;
+pub mod error;
+pub mod golden;
 pub mod graphics;
 pub mod handle;
+pub mod image;
 pub mod input;
+pub mod mesh;
+pub mod mipmap;
+pub mod offscreen;
 pub mod pipeline;
 pub mod render_pass;
 pub mod renderer;
 pub mod run;
+pub mod scene;
+pub mod skybox;
 pub mod shader;
 pub mod script;
 pub mod rcmut;
+
+pub use error::Error;
@@ -0,0 +1,190 @@
+pub mod obj;
+pub mod primitives;
+
+use cgmath::{InnerSpace, Point2, Point3, Vector3, Vector4, Zero};
+
+use crate::{error::Error, pipeline::Index};
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MeshVertex {
+    pub position: Point3<f32>,
+    pub normal: Vector3<f32>,
+    pub uv: Point2<f32>,
+    // xyz is the tangent along +u, w the bitangent handedness (±1).
+    pub tangent: Vector4<f32>,
+}
+
+impl MeshVertex {
+    pub fn new(position: Point3<f32>, normal: Vector3<f32>, uv: Point2<f32>) -> Self {
+        Self {
+            position,
+            normal,
+            uv,
+            tangent: Vector4::new(1.0, 0.0, 0.0, 1.0),
+        }
+    }
+}
+
+pub trait FromMeshVertex {
+    fn from_mesh_vertex(vertex: &MeshVertex) -> Self;
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Debug, Clone)]
+pub struct MeshData {
+    pub vertices: Vec<MeshVertex>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    pub fn get_vertices<T: FromMeshVertex>(&self) -> Vec<T> {
+        self.vertices.iter().map(T::from_mesh_vertex).collect()
+    }
+
+    pub fn get_indices<I: Index + std::convert::TryFrom<u32>>(&self) -> Result<Vec<I>, Error> {
+        self.indices
+            .iter()
+            .map(|index| {
+                I::try_from(*index).map_err(|_| {
+                    Error::Validation(format!(
+                        "Index {} does not fit the requested index format",
+                        index
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    pub fn get_geometry_data<T: FromMeshVertex, I: Index + std::convert::TryFrom<u32>>(
+        &self,
+    ) -> Result<(Vec<T>, Vec<I>), Error> {
+        Ok((self.get_vertices(), self.get_indices()?))
+    }
+
+    pub fn compute_normals(&mut self) {
+        self.compute_normals_where(|_| true);
+    }
+
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![Vector3::<f32>::zero(); self.vertices.len()];
+        let mut bitangents = vec![Vector3::<f32>::zero(); self.vertices.len()];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [
+                &self.vertices[triangle[0] as usize],
+                &self.vertices[triangle[1] as usize],
+                &self.vertices[triangle[2] as usize],
+            ];
+            let edge_1 = b.position - a.position;
+            let edge_2 = c.position - a.position;
+            let delta_uv_1 = b.uv - a.uv;
+            let delta_uv_2 = c.uv - a.uv;
+
+            let determinant = delta_uv_1.x * delta_uv_2.y - delta_uv_2.x * delta_uv_1.y;
+            if determinant.abs() <= std::f32::EPSILON {
+                continue;
+            }
+            let r = 1.0 / determinant;
+
+            let tangent = (edge_1 * delta_uv_2.y - edge_2 * delta_uv_1.y) * r;
+            let bitangent = (edge_2 * delta_uv_1.x - edge_1 * delta_uv_2.x) * r;
+            for index in triangle.iter() {
+                tangents[*index as usize] += tangent;
+                bitangents[*index as usize] += bitangent;
+            }
+        }
+
+        for (vertex, (tangent, bitangent)) in self
+            .vertices
+            .iter_mut()
+            .zip(tangents.iter().zip(bitangents.iter()))
+        {
+            let normal = vertex.normal;
+            let mut orthogonal = tangent - normal * normal.dot(*tangent);
+            if orthogonal.magnitude2() <= std::f32::EPSILON {
+                // No usable UV gradient; any vector perpendicular to the normal will do.
+                let axis = if normal.x.abs() < 0.9 {
+                    Vector3::unit_x()
+                } else {
+                    Vector3::unit_y()
+                };
+                orthogonal = axis - normal * normal.dot(axis);
+            }
+            let orthogonal = orthogonal.normalize();
+
+            let handedness = if normal.cross(orthogonal).dot(*bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            vertex.tangent = orthogonal.extend(handedness);
+        }
+    }
+
+    /*-------------------------------------------------*/
+
+    // Area-weighted smooth normals for the selected vertices, shared between vertices with
+    // identical positions so that UV seams stay smooth.
+    pub(crate) fn compute_normals_where(&mut self, select: impl FnMut(usize) -> bool) {
+        let selected: Vec<bool> = (0..self.vertices.len()).map(select).collect();
+        if !selected.iter().any(|s| *s) {
+            return;
+        }
+
+        let mut position_keys = std::collections::HashMap::<[u32; 3], Vector3<f32>>::new();
+        let get_key = |position: &Point3<f32>| {
+            [
+                position.x.to_bits(),
+                position.y.to_bits(),
+                position.z.to_bits(),
+            ]
+        };
+
+        for triangle in self.indices.chunks_exact(3) {
+            let a = self.vertices[triangle[0] as usize].position;
+            let b = self.vertices[triangle[1] as usize].position;
+            let c = self.vertices[triangle[2] as usize].position;
+            let face_normal = (b - a).cross(c - a);
+
+            for index in triangle.iter() {
+                if selected[*index as usize] {
+                    let key = get_key(&self.vertices[*index as usize].position);
+                    *position_keys.entry(key).or_insert_with(Vector3::zero) += face_normal;
+                }
+            }
+        }
+
+        for (vertex, _) in self
+            .vertices
+            .iter_mut()
+            .zip(selected.iter())
+            .filter(|(_, selected)| **selected)
+        {
+            let normal = position_keys
+                .get(&get_key(&vertex.position))
+                .copied()
+                .unwrap_or_else(Vector3::zero);
+            vertex.normal = if normal.magnitude2() > 0.0 {
+                normal.normalize()
+            } else {
+                Vector3::unit_y()
+            };
+        }
+    }
+}
+
+impl Default for MeshData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,641 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Point2, Point3, Vector3, Zero};
+
+use super::{MeshData, MeshVertex};
+use crate::error::Error;
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Debug, Clone)]
+pub struct ObjMaterial {
+    pub name: String,
+
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shininess: f32,
+    pub opacity: f32,
+
+    // Resolved against the directory of the material library.
+    pub diffuse_texture: Option<String>,
+}
+
+impl ObjMaterial {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+
+            ambient: [0.0, 0.0, 0.0],
+            diffuse: [1.0, 1.0, 1.0],
+            specular: [0.0, 0.0, 0.0],
+            shininess: 0.0,
+            opacity: 1.0,
+
+            diffuse_texture: None,
+        }
+    }
+
+    pub fn load_library(path: &str) -> Result<Vec<Self>, Error> {
+        let source = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: String::from(path),
+            source,
+        })?;
+
+        Self::parse_library(&source, path)
+    }
+
+    pub fn parse_library(source: &str, path: &str) -> Result<Vec<Self>, Error> {
+        let base_dir = std::path::Path::new(path)
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new(""));
+
+        let mut materials = Vec::<Self>::new();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let mut tokens = Tokens::new(line, path, line_index + 1);
+            let keyword = match tokens.next() {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+
+            if keyword == "newmtl" {
+                materials.push(Self::new(tokens.rest()));
+                continue;
+            }
+
+            let material = match materials.last_mut() {
+                Some(material) => material,
+                None => return Err(tokens.error("Material property before 'newmtl'")),
+            };
+            match keyword {
+                "Ka" => material.ambient = tokens.parse_vec3()?,
+                "Kd" => material.diffuse = tokens.parse_vec3()?,
+                "Ks" => material.specular = tokens.parse_vec3()?,
+                "Ns" => material.shininess = tokens.parse_f32()?,
+                "d" => material.opacity = tokens.parse_f32()?,
+                "Tr" => material.opacity = 1.0 - tokens.parse_f32()?,
+                "map_Kd" => {
+                    let texture_path = base_dir.join(tokens.rest());
+                    material.diffuse_texture = Some(texture_path.to_string_lossy().into_owned());
+                }
+                _ => (),
+            }
+        }
+
+        Ok(materials)
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Debug, Clone)]
+pub struct ObjMesh {
+    pub name: String,
+    pub material: Option<usize>,
+    pub data: MeshData,
+}
+
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjModel {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let source = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: String::from(path),
+            source,
+        })?;
+
+        Self::parse(&source, path)
+    }
+
+    pub fn parse(source: &str, path: &str) -> Result<Self, Error> {
+        let base_dir = std::path::Path::new(path)
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new(""));
+
+        let mut positions = Vec::<Point3<f32>>::new();
+        let mut uvs = Vec::<Point2<f32>>::new();
+        let mut normals = Vec::<Vector3<f32>>::new();
+
+        let mut model = Self {
+            meshes: Vec::new(),
+            materials: Vec::new(),
+        };
+        let mut builder = MeshBuilder::new(String::new(), None);
+
+        for (line_index, line) in source.lines().enumerate() {
+            let mut tokens = Tokens::new(line, path, line_index + 1);
+            let keyword = match tokens.next() {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+
+            match keyword {
+                "v" => {
+                    let [x, y, z] = tokens.parse_vec3()?;
+                    positions.push(Point3::new(x, y, z));
+                }
+                "vt" => {
+                    let u = tokens.parse_f32()?;
+                    let v = tokens.parse_f32()?;
+                    // OBJ places the texture origin bottom-left, wgpu top-left.
+                    uvs.push(Point2::new(u, 1.0 - v));
+                }
+                "vn" => {
+                    let [x, y, z] = tokens.parse_vec3()?;
+                    normals.push(Vector3::new(x, y, z));
+                }
+                "f" => {
+                    let mut face = Vec::<u32>::new();
+                    while let Some(corner) = tokens.next() {
+                        let key = tokens.parse_corner(
+                            corner,
+                            positions.len(),
+                            uvs.len(),
+                            normals.len(),
+                        )?;
+                        face.push(builder.get_vertex(key, &positions, &uvs, &normals));
+                    }
+                    if face.len() < 3 {
+                        return Err(tokens.error("Face has fewer than 3 vertices"));
+                    }
+
+                    let face_positions: Vec<Point3<f32>> = face
+                        .iter()
+                        .map(|index| builder.data.vertices[*index as usize].position)
+                        .collect();
+                    for [a, b, c] in triangulate_face(&face_positions) {
+                        builder
+                            .data
+                            .indices
+                            .extend_from_slice(&[face[a], face[b], face[c]]);
+                    }
+                }
+                "o" | "g" => {
+                    let name = String::from(tokens.rest());
+                    let material = builder.material;
+                    builder.finish(&mut model.meshes);
+                    builder = MeshBuilder::new(name, material);
+                }
+                "usemtl" => {
+                    let name = tokens.rest();
+                    let material = model.materials.iter().position(|m| m.name == name);
+                    if material.is_none() {
+                        log::warn!("Unknown OBJ material [{}] in [{}]", name, path);
+                    }
+
+                    let mesh_name = builder.name.clone();
+                    builder.finish(&mut model.meshes);
+                    builder = MeshBuilder::new(mesh_name, material);
+                }
+                // A missing library only loses the materials, faces fall back to no material.
+                "mtllib" => {
+                    let library_path = base_dir.join(tokens.rest());
+                    match ObjMaterial::load_library(&library_path.to_string_lossy()) {
+                        Ok(materials) => model.materials.extend(materials),
+                        Err(Error::Io { path, source }) => {
+                            log::warn!("Could not load OBJ material library [{}]: {}", path, source)
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                _ => (),
+            }
+        }
+        builder.finish(&mut model.meshes);
+
+        Ok(model)
+    }
+
+    pub fn get_merged_mesh(&self) -> MeshData {
+        let mut merged = MeshData::new();
+        for mesh in self.meshes.iter() {
+            let offset = merged.vertices.len() as u32;
+            merged.vertices.extend_from_slice(&mesh.data.vertices);
+            merged
+                .indices
+                .extend(mesh.data.indices.iter().map(|index| index + offset));
+        }
+
+        merged
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+type CornerKey = (u32, Option<u32>, Option<u32>);
+
+struct MeshBuilder {
+    name: String,
+    material: Option<usize>,
+
+    data: MeshData,
+    vertex_map: HashMap<CornerKey, u32>,
+    missing_normals: Vec<bool>,
+}
+
+impl MeshBuilder {
+    fn new(name: String, material: Option<usize>) -> Self {
+        Self {
+            name,
+            material,
+
+            data: MeshData::new(),
+            vertex_map: HashMap::new(),
+            missing_normals: Vec::new(),
+        }
+    }
+
+    fn get_vertex(
+        &mut self,
+        key: CornerKey,
+        positions: &[Point3<f32>],
+        uvs: &[Point2<f32>],
+        normals: &[Vector3<f32>],
+    ) -> u32 {
+        let data = &mut self.data;
+        let missing_normals = &mut self.missing_normals;
+
+        *self.vertex_map.entry(key).or_insert_with(|| {
+            let (position, uv, normal) = key;
+            data.vertices.push(MeshVertex::new(
+                positions[position as usize],
+                normal.map_or_else(Vector3::zero, |n| normals[n as usize]),
+                uv.map_or(Point2::new(0.0, 0.0), |t| uvs[t as usize]),
+            ));
+            missing_normals.push(normal.is_none());
+
+            (data.vertices.len() - 1) as u32
+        })
+    }
+
+    fn finish(mut self, meshes: &mut Vec<ObjMesh>) {
+        if self.data.indices.is_empty() {
+            return;
+        }
+
+        let missing_normals = self.missing_normals;
+        self.data
+            .compute_normals_where(|index| missing_normals[index]);
+        self.data.compute_tangents();
+
+        meshes.push(ObjMesh {
+            name: self.name,
+            material: self.material,
+            data: self.data,
+        });
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+// Splits a planar polygon into triangles by ear clipping, keeping the face winding. Returns
+// indices into `positions`; self-intersecting or degenerate faces fall back to a fan.
+fn triangulate_face(positions: &[Point3<f32>]) -> Vec<[usize; 3]> {
+    let n_corners = positions.len();
+    let fan = |corners: &[usize]| -> Vec<[usize; 3]> {
+        (1..corners.len() - 1)
+            .map(|i| [corners[0], corners[i], corners[i + 1]])
+            .collect()
+    };
+    if n_corners == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // Newell's method, robust for non-convex and slightly non-planar polygons.
+    let mut normal = Vector3::<f32>::zero();
+    for (i, a) in positions.iter().enumerate() {
+        let b = positions[(i + 1) % n_corners];
+        normal.x += (a.y - b.y) * (a.z + b.z);
+        normal.y += (a.z - b.z) * (a.x + b.x);
+        normal.z += (a.x - b.x) * (a.y + b.y);
+    }
+    let corners: Vec<usize> = (0..n_corners).collect();
+    if normal.magnitude2() <= std::f32::EPSILON {
+        return fan(&corners);
+    }
+
+    // Projects on the plane of the two axes most orthogonal to the normal, counter-clockwise.
+    let abs_normal = Vector3::new(normal.x.abs(), normal.y.abs(), normal.z.abs());
+    let points: Vec<Point2<f32>> = positions
+        .iter()
+        .map(|p| {
+            if abs_normal.x >= abs_normal.y && abs_normal.x >= abs_normal.z {
+                Point2::new(p.y, p.z * normal.x.signum())
+            } else if abs_normal.y >= abs_normal.z {
+                Point2::new(p.z, p.x * normal.y.signum())
+            } else {
+                Point2::new(p.x, p.y * normal.z.signum())
+            }
+        })
+        .collect();
+    let cross = |a: usize, b: usize, c: usize| {
+        let ab = points[b] - points[a];
+        let ac = points[c] - points[a];
+        ab.x * ac.y - ab.y * ac.x
+    };
+
+    let mut remaining = corners;
+    let mut triangles = Vec::with_capacity(n_corners - 2);
+    while remaining.len() > 3 {
+        let n_remaining = remaining.len();
+        let ear = (0..n_remaining).find(|i| {
+            let prev = remaining[(i + n_remaining - 1) % n_remaining];
+            let corner = remaining[*i];
+            let next = remaining[(i + 1) % n_remaining];
+            if cross(prev, corner, next) <= 0.0 {
+                return false;
+            }
+
+            !remaining
+                .iter()
+                .filter(|other| **other != prev && **other != corner && **other != next)
+                .any(|other| {
+                    cross(prev, corner, *other) >= 0.0
+                        && cross(corner, next, *other) >= 0.0
+                        && cross(next, prev, *other) >= 0.0
+                })
+        });
+
+        match ear {
+            Some(i) => {
+                let prev = remaining[(i + n_remaining - 1) % n_remaining];
+                let next = remaining[(i + 1) % n_remaining];
+                triangles.push([prev, remaining[i], next]);
+                remaining.remove(i);
+            }
+            None => {
+                triangles.extend(fan(&remaining));
+                return triangles;
+            }
+        }
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+
+    triangles
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+struct Tokens<'a> {
+    line: &'a str,
+    tokens: std::str::SplitWhitespace<'a>,
+
+    path: &'a str,
+    line_number: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str, path: &'a str, line_number: usize) -> Self {
+        let line = line.split('#').next().unwrap_or("").trim();
+        Self {
+            line,
+            tokens: line.split_whitespace(),
+
+            path,
+            line_number,
+        }
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.tokens.next()
+    }
+
+    fn rest(&self) -> &'a str {
+        self.line
+            .splitn(2, char::is_whitespace)
+            .nth(1)
+            .unwrap_or("")
+            .trim()
+    }
+
+    fn parse_f32(&mut self) -> Result<f32, Error> {
+        let token = self.next().ok_or_else(|| self.error("Expected a number"))?;
+        token
+            .parse::<f32>()
+            .map_err(|_| self.error(&format!("Invalid number '{}'", token)))
+    }
+
+    fn parse_vec3(&mut self) -> Result<[f32; 3], Error> {
+        Ok([self.parse_f32()?, self.parse_f32()?, self.parse_f32()?])
+    }
+
+    fn parse_corner(
+        &self,
+        corner: &str,
+        n_positions: usize,
+        n_uvs: usize,
+        n_normals: usize,
+    ) -> Result<CornerKey, Error> {
+        let mut parts = corner.split('/');
+
+        let position = self.resolve_index(parts.next(), n_positions)?;
+        let uv = self.resolve_index(parts.next(), n_uvs)?;
+        let normal = self.resolve_index(parts.next(), n_normals)?;
+
+        match position {
+            Some(position) => Ok((position, uv, normal)),
+            None => Err(self.error(&format!("Face corner '{}' has no position", corner))),
+        }
+    }
+
+    // OBJ indices are 1-based, negative values count back from the latest element.
+    fn resolve_index(&self, token: Option<&str>, count: usize) -> Result<Option<u32>, Error> {
+        let token = match token {
+            None | Some("") => return Ok(None),
+            Some(token) => token,
+        };
+
+        let index = token
+            .parse::<i64>()
+            .map_err(|_| self.error(&format!("Invalid index '{}'", token)))?;
+        let resolved = if index < 0 {
+            count as i64 + index
+        } else {
+            index - 1
+        };
+
+        if resolved < 0 || resolved >= count as i64 {
+            return Err(self.error(&format!("Index '{}' is out of range", token)));
+        }
+
+        Ok(Some(resolved as u32))
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::Parse {
+            path: String::from(self.path),
+            line: self.line_number,
+            message: String::from(message),
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> ObjModel {
+        ObjModel::parse(source, "test.obj").unwrap()
+    }
+
+    fn get_triangle_normal(data: &MeshData, triangle: &[u32]) -> Vector3<f32> {
+        let a = data.vertices[triangle[0] as usize].position;
+        let b = data.vertices[triangle[1] as usize].position;
+        let c = data.vertices[triangle[2] as usize].position;
+
+        (b - a).cross(c - a)
+    }
+
+    const QUAD: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+";
+
+    #[test]
+    fn resolves_one_based_and_negative_indices() {
+        let model = parse(&format!("{}f 1 2 3\nf -4 -2 -1\n", QUAD));
+        let data = &model.meshes[0].data;
+
+        assert_eq!(data.vertices.len(), 4);
+        assert_eq!(data.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(data.vertices[0].position, Point3::new(0.0, 0.0, 0.0));
+        assert_eq!(data.vertices[3].position, Point3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        let result = ObjModel::parse(&format!("{}f 1 2 5\n", QUAD), "test.obj");
+
+        match result {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 6),
+            _ => panic!("expected a parse error"),
+        }
+        assert!(ObjModel::parse(&format!("{}f 0 1 2\n", QUAD), "test.obj").is_err());
+        assert!(ObjModel::parse(&format!("{}f -5 1 2\n", QUAD), "test.obj").is_err());
+    }
+
+    #[test]
+    fn deduplicates_identical_corners() {
+        let source = format!(
+            "{}vt 0 0\nvt 1 1\nf 1/1 2/1 3/1\nf 1/1 3/1 4/1\nf 1/2 2/1 3/1\n",
+            QUAD
+        );
+        let data = &parse(&source).meshes[0].data;
+
+        // The third face only adds the corner using a different texture coordinate.
+        assert_eq!(data.vertices.len(), 5);
+        assert_eq!(data.indices.len(), 9);
+        assert_eq!(&data.indices[..6], &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(&data.indices[6..], &[4, 1, 2]);
+    }
+
+    #[test]
+    fn triangulates_quads_with_the_face_winding() {
+        let data = &parse(&format!("{}f 1 2 3 4\n", QUAD)).meshes[0].data;
+
+        assert_eq!(data.vertices.len(), 4);
+        assert_eq!(data.indices.len(), 6);
+        for triangle in data.indices.chunks_exact(3) {
+            let normal = get_triangle_normal(data, triangle);
+            assert!(normal.z > 0.0);
+            assert!((normal.z - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn triangulates_concave_faces() {
+        // L-shape with a reflex corner at (1, 1), its area is 3.
+        let source = "
+v 0 0 0
+v 2 0 0
+v 2 1 0
+v 1 1 0
+v 1 2 0
+v 0 2 0
+f 1 2 3 4 5 6
+";
+        let data = &parse(source).meshes[0].data;
+
+        assert_eq!(data.indices.len(), 3 * 4);
+        let mut area = 0.0;
+        for triangle in data.indices.chunks_exact(3) {
+            let normal = get_triangle_normal(data, triangle);
+            assert!(normal.z > 0.0, "triangle {:?} is flipped", triangle);
+            area += normal.z / 2.0;
+        }
+        assert!((area - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn triangulates_faces_outside_the_xy_plane() {
+        let corners = [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, 2.0),
+            Point3::new(0.0, 1.0, 2.0),
+            Point3::new(0.0, 1.0, 1.0),
+            Point3::new(0.0, 2.0, 1.0),
+            Point3::new(0.0, 2.0, 0.0),
+        ];
+        let face_normal = (corners[1] - corners[0]).cross(corners[2] - corners[0]);
+
+        let triangles = triangulate_face(&corners);
+        assert_eq!(triangles.len(), 4);
+        for [a, b, c] in triangles {
+            let normal = (corners[b] - corners[a]).cross(corners[c] - corners[a]);
+            assert!(normal.dot(face_normal) > 0.0);
+        }
+    }
+
+    #[test]
+    fn generates_missing_normals_only() {
+        let source = format!("{}vn 1 0 0\nf 1 2 3\nf 1//1 3//1 4//1\n", QUAD);
+        let data = &parse(&source).meshes[0].data;
+
+        let generated = &data.vertices[..3];
+        for vertex in generated {
+            assert!((vertex.normal - Vector3::new(0.0, 0.0, 1.0)).magnitude() < 1e-6);
+        }
+        let given = &data.vertices[3..];
+        for vertex in given {
+            assert_eq!(vertex.normal, Vector3::new(1.0, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn splits_meshes_and_warns_on_missing_libraries() {
+        let source = format!(
+            "mtllib missing.mtl\n{}o first\nusemtl unknown\nf 1 2 3\no second\nf 1 3 4\n",
+            QUAD
+        );
+        let model = parse(&source);
+
+        assert!(model.materials.is_empty());
+        assert_eq!(model.meshes.len(), 2);
+        assert_eq!(model.meshes[0].name, "first");
+        assert_eq!(model.meshes[0].material, None);
+        assert_eq!(model.meshes[1].name, "second");
+        assert_eq!(model.get_merged_mesh().indices, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn resolves_textures_against_the_library_directory() {
+        let source = "newmtl stone\nKd 0.5 0.25 1\nmap_Kd textures/stone.png\n";
+        let materials = ObjMaterial::parse_library(source, "models/stone.mtl").unwrap();
+
+        assert_eq!(materials.len(), 1);
+        assert_eq!(materials[0].diffuse, [0.5, 0.25, 1.0]);
+        let expected = std::path::Path::new("models").join("textures/stone.png");
+        assert_eq!(
+            materials[0].diffuse_texture.as_deref(),
+            Some(&*expected.to_string_lossy())
+        );
+    }
+}
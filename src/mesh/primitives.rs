@@ -0,0 +1,629 @@
+use std::{collections::HashMap, f32::consts::PI};
+
+use cgmath::{EuclideanSpace, InnerSpace, Point2, Point3, Vector3};
+
+use super::{MeshData, MeshVertex};
+
+/*--------------------------------------------------------------------------------------------------*/
+
+impl MeshData {
+    pub fn plane(width: f32, depth: f32, subdivisions: u32) -> Self {
+        let mut data = Self::new();
+        data.add_face(
+            Point3::new(-width / 2.0, 0.0, depth / 2.0),
+            Vector3::new(width, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, -depth),
+            subdivisions.max(1),
+        );
+        data.compute_tangents();
+
+        data
+    }
+
+    pub fn cuboid(size: Vector3<f32>, subdivisions: u32) -> Self {
+        let (x, y, z) = (size.x, size.y, size.z);
+        let (hx, hy, hz) = (x / 2.0, y / 2.0, z / 2.0);
+        let subdivisions = subdivisions.max(1);
+
+        let mut data = Self::new();
+        let faces = [
+            // +X, -X
+            (
+                Point3::new(hx, -hy, hz),
+                Vector3::new(0.0, 0.0, -z),
+                Vector3::new(0.0, y, 0.0),
+            ),
+            (
+                Point3::new(-hx, -hy, -hz),
+                Vector3::new(0.0, 0.0, z),
+                Vector3::new(0.0, y, 0.0),
+            ),
+            // +Y, -Y
+            (
+                Point3::new(-hx, hy, hz),
+                Vector3::new(x, 0.0, 0.0),
+                Vector3::new(0.0, 0.0, -z),
+            ),
+            (
+                Point3::new(-hx, -hy, -hz),
+                Vector3::new(x, 0.0, 0.0),
+                Vector3::new(0.0, 0.0, z),
+            ),
+            // +Z, -Z
+            (
+                Point3::new(-hx, -hy, hz),
+                Vector3::new(x, 0.0, 0.0),
+                Vector3::new(0.0, y, 0.0),
+            ),
+            (
+                Point3::new(hx, -hy, -hz),
+                Vector3::new(-x, 0.0, 0.0),
+                Vector3::new(0.0, y, 0.0),
+            ),
+        ];
+        for (origin, u_axis, v_axis) in faces.iter() {
+            data.add_face(*origin, *u_axis, *v_axis, subdivisions);
+        }
+        data.compute_tangents();
+
+        data
+    }
+
+    pub fn uv_sphere(radius: f32, segments: u32, rings: u32) -> Self {
+        let rings = rings.max(2);
+        let profile: Vec<(f32, f32, f32)> = (0..=rings)
+            .map(|ring| {
+                let phi = PI * ring as f32 / rings as f32;
+                (radius * phi.sin(), radius * phi.cos(), phi)
+            })
+            .collect();
+
+        Self::sphere_profile(&profile, segments, |_, phi| phi / PI)
+    }
+
+    pub fn icosphere(radius: f32, subdivisions: u32) -> Self {
+        let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+        let mut positions: Vec<Vector3<f32>> = [
+            [-1.0, t, 0.0],
+            [1.0, t, 0.0],
+            [-1.0, -t, 0.0],
+            [1.0, -t, 0.0],
+            [0.0, -1.0, t],
+            [0.0, 1.0, t],
+            [0.0, -1.0, -t],
+            [0.0, 1.0, -t],
+            [t, 0.0, -1.0],
+            [t, 0.0, 1.0],
+            [-t, 0.0, -1.0],
+            [-t, 0.0, 1.0],
+        ]
+        .iter()
+        .map(|position| Vector3::from(*position).normalize())
+        .collect();
+        let mut triangles: Vec<[u32; 3]> = vec![
+            [0, 11, 5],
+            [0, 5, 1],
+            [0, 1, 7],
+            [0, 7, 10],
+            [0, 10, 11],
+            [1, 5, 9],
+            [5, 11, 4],
+            [11, 10, 2],
+            [10, 7, 6],
+            [7, 1, 8],
+            [3, 9, 4],
+            [3, 4, 2],
+            [3, 2, 6],
+            [3, 6, 8],
+            [3, 8, 9],
+            [4, 9, 5],
+            [2, 4, 11],
+            [6, 2, 10],
+            [8, 6, 7],
+            [9, 8, 1],
+        ];
+
+        for _ in 0..subdivisions {
+            let mut midpoints = HashMap::<(u32, u32), u32>::new();
+            let mut get_midpoint = |a: u32, b: u32| {
+                let key = (a.min(b), a.max(b));
+                *midpoints.entry(key).or_insert_with(|| {
+                    let midpoint = (positions[a as usize] + positions[b as usize]).normalize();
+                    positions.push(midpoint);
+                    (positions.len() - 1) as u32
+                })
+            };
+
+            triangles = triangles
+                .iter()
+                .flat_map(|[a, b, c]| {
+                    let ab = get_midpoint(*a, *b);
+                    let bc = get_midpoint(*b, *c);
+                    let ca = get_midpoint(*c, *a);
+                    vec![[*a, ab, ca], [*b, bc, ab], [*c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        // Equirectangular UVs with the seam at u = 0/1 (-X). Triangles crossing it get their own
+        // copies of the corners on the u < 0.5 side shifted by one, corners lying on the seam are
+        // placed on the side of the rest of their triangle, and pole corners, where u is
+        // undefined, take the mean u of the other two.
+        let is_pole = |normal: &Vector3<f32>| normal.x * normal.x + normal.z * normal.z <= 1e-12;
+        let is_on_seam = |normal: &Vector3<f32>| normal.z.abs() <= 1e-6 && normal.x < 0.0;
+
+        let mut data = Self::new();
+        data.vertices = positions
+            .iter()
+            .map(|normal| {
+                let u = if is_on_seam(normal) {
+                    0.0
+                } else {
+                    0.5 + normal.z.atan2(normal.x) / (2.0 * PI)
+                };
+                let v = normal.y.max(-1.0).min(1.0).acos() / PI;
+                MeshVertex::new(
+                    Point3::from_vec(normal * radius),
+                    *normal,
+                    Point2::new(u, v),
+                )
+            })
+            .collect();
+
+        let mut seam_copies = HashMap::<u32, u32>::new();
+        for triangle in triangles.iter() {
+            let mut corners = *triangle;
+            let normals: Vec<Vector3<f32>> = corners
+                .iter()
+                .map(|corner| data.vertices[*corner as usize].normal)
+                .collect();
+            let is_regular =
+                |index: usize| !is_pole(&normals[index]) && !is_on_seam(&normals[index]);
+            let get_u = |vertices: &[MeshVertex], corners: &[u32; 3], index: usize| {
+                vertices[corners[index] as usize].uv.x
+            };
+
+            let (min_u, max_u) = (0..3)
+                .filter(|index| is_regular(*index))
+                .map(|index| get_u(&data.vertices, &corners, index))
+                .fold((std::f32::MAX, std::f32::MIN), |(min, max), u| {
+                    (min.min(u), max.max(u))
+                });
+            let crosses_seam = max_u - min_u > 0.5;
+            let shifted: Vec<usize> = (0..3)
+                .filter(|index| {
+                    if is_regular(*index) {
+                        crosses_seam && get_u(&data.vertices, &corners, *index) < 0.5
+                    } else {
+                        !is_pole(&normals[*index]) && (crosses_seam || min_u + max_u > 1.0)
+                    }
+                })
+                .collect();
+            for index in shifted {
+                let vertices = &mut data.vertices;
+                let corner = corners[index];
+                corners[index] = *seam_copies.entry(corner).or_insert_with(|| {
+                    let mut copy = vertices[corner as usize];
+                    copy.uv.x += 1.0;
+                    vertices.push(copy);
+                    (vertices.len() - 1) as u32
+                });
+            }
+
+            for pole_index in (0..3).filter(|index| is_pole(&normals[*index])) {
+                let others: Vec<f32> = (0..3)
+                    .filter(|index| !is_pole(&normals[*index]))
+                    .map(|index| get_u(&data.vertices, &corners, index))
+                    .collect();
+                let mut copy = data.vertices[corners[pole_index] as usize];
+                copy.uv.x = others.iter().sum::<f32>() / others.len().max(1) as f32;
+                data.vertices.push(copy);
+                corners[pole_index] = (data.vertices.len() - 1) as u32;
+            }
+
+            data.indices.extend_from_slice(&corners);
+        }
+        data.compute_tangents();
+
+        data
+    }
+
+    pub fn cylinder(radius: f32, height: f32, segments: u32, height_segments: u32) -> Self {
+        Self::frustum(radius, radius, height, segments, height_segments)
+    }
+
+    pub fn cone(radius: f32, height: f32, segments: u32, height_segments: u32) -> Self {
+        Self::frustum(radius, 0.0, height, segments, height_segments)
+    }
+
+    pub fn torus(
+        major_radius: f32,
+        minor_radius: f32,
+        major_segments: u32,
+        minor_segments: u32,
+    ) -> Self {
+        let major_segments = major_segments.max(3);
+        let minor_segments = minor_segments.max(3);
+
+        let mut data = Self::new();
+        for j in 0..=minor_segments {
+            let phi = 2.0 * PI * j as f32 / minor_segments as f32;
+            for i in 0..=major_segments {
+                let theta = 2.0 * PI * i as f32 / major_segments as f32;
+                let outward = Vector3::new(theta.sin(), 0.0, theta.cos());
+                let normal = outward * phi.cos() + Vector3::unit_y() * phi.sin();
+
+                data.vertices.push(MeshVertex::new(
+                    Point3::from_vec(outward * major_radius + normal * minor_radius),
+                    normal,
+                    Point2::new(
+                        i as f32 / major_segments as f32,
+                        j as f32 / minor_segments as f32,
+                    ),
+                ));
+            }
+        }
+        data.add_grid_indices(0, major_segments, minor_segments);
+        data.compute_tangents();
+
+        data
+    }
+
+    pub fn capsule(
+        radius: f32,
+        height: f32,
+        segments: u32,
+        rings: u32,
+        height_segments: u32,
+    ) -> Self {
+        let rings = rings.max(1);
+        let height_segments = height_segments.max(1);
+        let half_height = height / 2.0;
+
+        let mut profile = Vec::<(f32, f32, f32)>::new();
+        for ring in 0..=rings {
+            let phi = PI / 2.0 * ring as f32 / rings as f32;
+            profile.push((radius * phi.sin(), half_height + radius * phi.cos(), phi));
+        }
+        for row in 1..height_segments {
+            let y = half_height - height * row as f32 / height_segments as f32;
+            profile.push((radius, y, PI / 2.0));
+        }
+        for ring in 0..=rings {
+            let phi = PI / 2.0 + PI / 2.0 * ring as f32 / rings as f32;
+            profile.push((radius * phi.sin(), -half_height + radius * phi.cos(), phi));
+        }
+
+        let total_height = height + 2.0 * radius;
+        Self::sphere_profile(&profile, segments, |y, _| {
+            (half_height + radius - y) / total_height
+        })
+    }
+
+    /*-------------------------------------------------*/
+
+    // Grid on the face spanned from `origin` by `u_axis` and `v_axis`; u × v is the outward normal.
+    fn add_face(
+        &mut self,
+        origin: Point3<f32>,
+        u_axis: Vector3<f32>,
+        v_axis: Vector3<f32>,
+        subdivisions: u32,
+    ) {
+        let normal = u_axis.cross(v_axis).normalize();
+        let base = self.vertices.len() as u32;
+
+        for j in 0..=subdivisions {
+            let t = j as f32 / subdivisions as f32;
+            for i in 0..=subdivisions {
+                let s = i as f32 / subdivisions as f32;
+                self.vertices.push(MeshVertex::new(
+                    origin + u_axis * s + v_axis * t,
+                    normal,
+                    Point2::new(s, 1.0 - t),
+                ));
+            }
+        }
+        self.add_grid_indices(base, subdivisions, subdivisions);
+    }
+
+    // Revolves a (radius, y, phi) profile around Y, with phi the polar angle of the normal.
+    fn sphere_profile(
+        profile: &[(f32, f32, f32)],
+        segments: u32,
+        get_v: impl Fn(f32, f32) -> f32,
+    ) -> Self {
+        let segments = segments.max(3);
+
+        let mut data = Self::new();
+        for (ring_radius, y, phi) in profile.iter() {
+            // sin(PI) is slightly negative in f32, the clamp collapses the bottom pole exactly.
+            let ring_radius = ring_radius.max(0.0);
+            for segment in 0..=segments {
+                let theta = 2.0 * PI * segment as f32 / segments as f32;
+                let (sin_theta, cos_theta) = theta.sin_cos();
+
+                data.vertices.push(MeshVertex::new(
+                    Point3::new(ring_radius * cos_theta, *y, ring_radius * sin_theta),
+                    Vector3::new(phi.sin() * cos_theta, phi.cos(), phi.sin() * sin_theta),
+                    Point2::new(segment as f32 / segments as f32, get_v(*y, *phi)),
+                ));
+            }
+        }
+        data.add_grid_indices(0, segments, profile.len() as u32 - 1);
+        data.compute_tangents();
+
+        data
+    }
+
+    fn frustum(
+        bottom_radius: f32,
+        top_radius: f32,
+        height: f32,
+        segments: u32,
+        height_segments: u32,
+    ) -> Self {
+        let segments = segments.max(3);
+        let height_segments = height_segments.max(1);
+        let half_height = height / 2.0;
+        let slope = (bottom_radius - top_radius) / height;
+
+        let mut data = Self::new();
+        for j in 0..=height_segments {
+            let t = j as f32 / height_segments as f32;
+            let radius = bottom_radius + (top_radius - bottom_radius) * t;
+            let y = -half_height + height * t;
+
+            for i in 0..=segments {
+                let theta = 2.0 * PI * i as f32 / segments as f32;
+                let (sin_theta, cos_theta) = theta.sin_cos();
+
+                data.vertices.push(MeshVertex::new(
+                    Point3::new(radius * sin_theta, y, radius * cos_theta),
+                    Vector3::new(sin_theta, slope, cos_theta).normalize(),
+                    Point2::new(i as f32 / segments as f32, 1.0 - t),
+                ));
+            }
+        }
+        data.add_grid_indices(0, segments, height_segments);
+
+        if bottom_radius > 0.0 {
+            data.add_cap(bottom_radius, -half_height, segments, false);
+        }
+        if top_radius > 0.0 {
+            data.add_cap(top_radius, half_height, segments, true);
+        }
+        data.compute_tangents();
+
+        data
+    }
+
+    fn add_cap(&mut self, radius: f32, y: f32, segments: u32, facing_up: bool) {
+        let normal = if facing_up {
+            Vector3::unit_y()
+        } else {
+            -Vector3::unit_y()
+        };
+
+        let center = self.vertices.len() as u32;
+        self.vertices.push(MeshVertex::new(
+            Point3::new(0.0, y, 0.0),
+            normal,
+            Point2::new(0.5, 0.5),
+        ));
+        for i in 0..=segments {
+            let theta = 2.0 * PI * i as f32 / segments as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+
+            self.vertices.push(MeshVertex::new(
+                Point3::new(radius * sin_theta, y, radius * cos_theta),
+                normal,
+                Point2::new(0.5 + 0.5 * sin_theta, 0.5 + 0.5 * cos_theta),
+            ));
+        }
+
+        for i in 0..segments {
+            let (a, b) = (center + 1 + i, center + 2 + i);
+            if facing_up {
+                self.indices.extend_from_slice(&[center, a, b]);
+            } else {
+                self.indices.extend_from_slice(&[center, b, a]);
+            }
+        }
+    }
+
+    // Quads between a (columns + 1) x (rows + 1) block of vertices starting at `base`,
+    // dropping the degenerate triangles that collapse at poles and apexes.
+    fn add_grid_indices(&mut self, base: u32, columns: u32, rows: u32) {
+        for j in 0..rows {
+            for i in 0..columns {
+                let a = base + j * (columns + 1) + i;
+                let b = a + 1;
+                let c = a + columns + 1;
+                let d = c + 1;
+
+                for triangle in [[a, b, d], [a, d, c]].iter() {
+                    if !self.is_degenerate(triangle) {
+                        self.indices.extend_from_slice(triangle);
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_degenerate(&self, triangle: &[u32; 3]) -> bool {
+        let a = self.vertices[triangle[0] as usize].position;
+        let b = self.vertices[triangle[1] as usize].position;
+        let c = self.vertices[triangle[2] as usize].position;
+
+        let edge_1 = b - a;
+        let edge_2 = c - a;
+        edge_1.cross(edge_2).magnitude2()
+            <= std::f32::EPSILON * edge_1.magnitude2() * edge_2.magnitude2()
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_face_normal(data: &MeshData, triangle: &[u32]) -> Vector3<f32> {
+        let a = data.vertices[triangle[0] as usize].position;
+        let b = data.vertices[triangle[1] as usize].position;
+        let c = data.vertices[triangle[2] as usize].position;
+
+        (b - a).cross(c - a)
+    }
+
+    // Every triangle is counter-clockwise when seen from the side its vertex normals point to.
+    fn assert_winding_follows_normals(data: &MeshData) {
+        for triangle in data.indices.chunks_exact(3) {
+            let vertex_normals: Vector3<f32> = triangle
+                .iter()
+                .map(|index| data.vertices[*index as usize].normal)
+                .sum();
+            assert!(
+                get_face_normal(data, triangle).dot(vertex_normals) > 0.0,
+                "triangle {:?} is wound against its normals",
+                triangle
+            );
+        }
+    }
+
+    // Closed shapes centered on the origin face away from it.
+    fn assert_outward(data: &MeshData) {
+        for triangle in data.indices.chunks_exact(3) {
+            let corner = data.vertices[triangle[0] as usize].position.to_vec();
+            assert!(
+                get_face_normal(data, triangle).dot(corner) > 0.0,
+                "triangle {:?} faces inward",
+                triangle
+            );
+        }
+    }
+
+    fn assert_unit_normals_and_tangents(data: &MeshData) {
+        for vertex in data.vertices.iter() {
+            let tangent = vertex.tangent.truncate();
+            assert!((vertex.normal.magnitude() - 1.0).abs() < 1e-4);
+            assert!((tangent.magnitude() - 1.0).abs() < 1e-4);
+            assert!(tangent.dot(vertex.normal).abs() < 1e-4);
+            assert_eq!(vertex.tangent.w.abs(), 1.0);
+        }
+    }
+
+    fn assert_valid(data: &MeshData, n_vertices: usize, n_triangles: usize) {
+        assert_eq!(data.vertices.len(), n_vertices);
+        assert_eq!(data.indices.len(), 3 * n_triangles);
+        assert!(data
+            .indices
+            .iter()
+            .all(|index| (*index as usize) < data.vertices.len()));
+        assert_winding_follows_normals(data);
+        assert_unit_normals_and_tangents(data);
+    }
+
+    #[test]
+    fn plane() {
+        let data = MeshData::plane(2.0, 1.0, 2);
+
+        assert_valid(&data, 9, 8);
+        for triangle in data.indices.chunks_exact(3) {
+            assert!(get_face_normal(&data, triangle).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn cuboid() {
+        let data = MeshData::cuboid(Vector3::new(1.0, 2.0, 3.0), 1);
+
+        assert_valid(&data, 24, 12);
+        assert_outward(&data);
+    }
+
+    #[test]
+    fn uv_sphere() {
+        let data = MeshData::uv_sphere(1.0, 8, 4);
+
+        // The first and last rows only keep one triangle per quad.
+        assert_valid(&data, 5 * 9, 2 * 2 * 8 + 2 * 8);
+        assert_outward(&data);
+    }
+
+    #[test]
+    fn icosphere() {
+        for subdivisions in 0..3 {
+            let data = MeshData::icosphere(2.0, subdivisions);
+            let n_triangles = 20 * 4_usize.pow(subdivisions);
+
+            assert_eq!(data.indices.len(), 3 * n_triangles);
+            assert!(data.vertices.len() > 10 * 4_usize.pow(subdivisions) + 2);
+            assert_winding_follows_normals(&data);
+            assert_unit_normals_and_tangents(&data);
+            assert_outward(&data);
+        }
+    }
+
+    #[test]
+    fn icosphere_splits_the_uv_seam() {
+        let data = MeshData::icosphere(1.0, 2);
+
+        for triangle in data.indices.chunks_exact(3) {
+            let us: Vec<f32> = triangle
+                .iter()
+                .map(|index| data.vertices[*index as usize].uv.x)
+                .collect();
+            let min_u = us.iter().cloned().fold(std::f32::MAX, f32::min);
+            let max_u = us.iter().cloned().fold(std::f32::MIN, f32::max);
+            assert!(
+                max_u - min_u < 0.25,
+                "triangle {:?} spans u {:?}",
+                triangle,
+                us
+            );
+        }
+    }
+
+    #[test]
+    fn cylinder() {
+        let data = MeshData::cylinder(1.0, 2.0, 8, 2);
+
+        assert_valid(&data, 3 * 9 + 2 * 10, 2 * 2 * 8 + 2 * 8);
+        assert_outward(&data);
+    }
+
+    #[test]
+    fn cone() {
+        let data = MeshData::cone(1.0, 2.0, 8, 1);
+
+        // The row reaching the apex only keeps one triangle per quad.
+        assert_valid(&data, 2 * 9 + 10, 8 + 8);
+        assert_outward(&data);
+    }
+
+    #[test]
+    fn torus() {
+        let data = MeshData::torus(2.0, 0.5, 8, 4);
+
+        assert_valid(&data, 5 * 9, 2 * 8 * 4);
+    }
+
+    #[test]
+    fn capsule() {
+        let data = MeshData::capsule(0.5, 1.0, 8, 2, 2);
+
+        // 3 rings per hemisphere and 1 row splitting the cylinder in 2.
+        assert_valid(&data, 7 * 9, 2 * 8 * 4 + 2 * 8);
+        assert_outward(&data);
+
+        let ys: Vec<f32> = data
+            .vertices
+            .iter()
+            .map(|vertex| vertex.position.y)
+            .collect();
+        assert!(ys.iter().any(|y| y.abs() < 1e-6));
+        assert!(ys.iter().all(|y| y.abs() <= 1.0 + 1e-6));
+    }
+}
@@ -0,0 +1,131 @@
+use std::{num::NonZeroU32, rc::Rc};
+
+use crate::{
+    blit::{self, Blitter},
+    error::Error,
+};
+
+// The depth of 1D and 2D textures counts array layers, which are not reduced.
+pub fn get_full_mip_level_count(size: wgpu::Extent3d, dimension: wgpu::TextureDimension) -> u32 {
+    let largest = match dimension {
+        wgpu::TextureDimension::D1 => size.width,
+        wgpu::TextureDimension::D2 => size.width.max(size.height),
+        wgpu::TextureDimension::D3 => size.width.max(size.height).max(size.depth),
+    };
+
+    32 - largest.max(1).leading_zeros()
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct MipmapGenerator {
+    blitter: Blitter,
+}
+
+impl MipmapGenerator {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self::try_new(device).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new(device: &wgpu::Device) -> Result<Self, Error> {
+        Ok(Self {
+            blitter: Blitter::try_new(device)?,
+        })
+    }
+
+    pub fn supports_format(format: wgpu::TextureFormat) -> bool {
+        Blitter::supports_format(format)
+    }
+
+    pub fn create_target(
+        &mut self,
+        device: &wgpu::Device,
+        texture: &wgpu::Texture,
+        format: wgpu::TextureFormat,
+        mip_level_count: u32,
+        layers: u32,
+    ) -> Result<MipmapTarget, Error> {
+        if !Self::supports_format(format) {
+            return Err(Error::Validation(format!(
+                "Mipmaps can't be generated on the GPU for format {:?}",
+                format
+            )));
+        }
+
+        let pipeline = Rc::clone(self.blitter.get_pipeline(device, format));
+
+        let mut levels = Vec::new();
+        for layer in 0..layers {
+            let views: Vec<wgpu::TextureView> = (0..mip_level_count)
+                .map(|level| {
+                    texture.create_view(&wgpu::TextureViewDescriptor {
+                        label: None,
+                        format: Some(format),
+                        dimension: Some(wgpu::TextureViewDimension::D2),
+                        aspect: wgpu::TextureAspect::All,
+                        base_mip_level: level,
+                        level_count: NonZeroU32::new(1),
+                        base_array_layer: layer,
+                        array_layer_count: NonZeroU32::new(1),
+                    })
+                })
+                .collect();
+
+            let bind_groups: Vec<wgpu::BindGroup> = views
+                .iter()
+                .take(views.len().saturating_sub(1))
+                .map(|source_view| self.blitter.create_bind_group(device, source_view))
+                .collect();
+
+            levels.extend(bind_groups.into_iter().zip(views.into_iter().skip(1)));
+        }
+
+        Ok(MipmapTarget { pipeline, levels })
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct MipmapTarget {
+    pipeline: Rc<wgpu::RenderPipeline>,
+    levels: Vec<(wgpu::BindGroup, wgpu::TextureView)>,
+}
+
+impl MipmapTarget {
+    pub fn encode(&self, encoder: &mut wgpu::CommandEncoder) {
+        for (bind_group, target_view) in self.levels.iter() {
+            blit::encode_blit(encoder, &self.pipeline, bind_group, target_view);
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_count(width: u32, height: u32, depth: u32, dimension: wgpu::TextureDimension) -> u32 {
+        get_full_mip_level_count(
+            wgpu::Extent3d {
+                width,
+                height,
+                depth,
+            },
+            dimension,
+        )
+    }
+
+    #[test]
+    fn counts_full_mip_chains() {
+        use wgpu::TextureDimension as D;
+
+        assert_eq!(get_count(1, 1, 1, D::D2), 1);
+        assert_eq!(get_count(256, 128, 1, D::D2), 9);
+        assert_eq!(get_count(255, 3, 1, D::D2), 8);
+        // Layers don't shrink, the depth of a 3D texture does.
+        assert_eq!(get_count(4, 4, 64, D::D2), 3);
+        assert_eq!(get_count(4, 4, 64, D::D3), 7);
+        assert_eq!(get_count(8, 1, 4, D::D1), 4);
+    }
+}
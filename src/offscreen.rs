@@ -0,0 +1,78 @@
+use wgpu;
+
+use crate::{capture::FrameCapture, error::Error};
+
+pub struct OffscreenTarget {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+
+    size: wgpu::Extent3d,
+    format: wgpu::TextureFormat,
+}
+
+impl OffscreenTarget {
+    pub fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Result<Self, Error> {
+        if !FrameCapture::supports_format(format) {
+            return Err(Error::Validation(format!(
+                "Offscreen targets can't be captured in format {:?}",
+                format
+            )));
+        }
+
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+
+            format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT
+                | wgpu::TextureUsage::COPY_SRC
+                | wgpu::TextureUsage::SAMPLED,
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Ok(Self {
+            texture,
+            view,
+
+            size,
+            format,
+        })
+    }
+
+    pub fn get_texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub fn get_view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn get_size(&self) -> wgpu::Extent3d {
+        self.size
+    }
+
+    pub fn get_format(&self) -> wgpu::TextureFormat {
+        self.format
+    }
+
+    pub fn capture(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Result<FrameCapture, Error> {
+        FrameCapture::read_texture(device, queue, &self.texture, self.size, self.format)
+    }
+}
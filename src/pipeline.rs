@@ -1,6 +1,9 @@
+use cgmath::{InnerSpace, Point3};
+
 use crate::{
     binding::{Binding, BindingLayout},
-    handle::{BindingHandle, BindingHandleLayout},
+    error::Error,
+    handle::{BindingHandle, BindingHandleLayout, SharedBindingHandle},
 };
 
 pub struct Shaders {
@@ -12,9 +15,15 @@ pub struct Shaders {
 
 pub trait Vertex {
     fn get_attribute_descriptors() -> Vec<wgpu::VertexAttributeDescriptor> {
+        Self::get_attribute_descriptors_from(0)
+    }
+
+    fn get_attribute_descriptors_from(
+        first_location: wgpu::ShaderLocation,
+    ) -> Vec<wgpu::VertexAttributeDescriptor> {
         let mut vertex_attribute_descriptors = Vec::<wgpu::VertexAttributeDescriptor>::new();
 
-        let mut shader_location: wgpu::ShaderLocation = 0;
+        let mut shader_location: wgpu::ShaderLocation = first_location;
         let mut offset: wgpu::BufferAddress = 0;
         for format in Self::get_attribute_formats().iter() {
             vertex_attribute_descriptors.push(wgpu::VertexAttributeDescriptor {
@@ -33,6 +42,18 @@ pub trait Vertex {
     fn get_attribute_formats() -> Vec<wgpu::VertexFormat>;
 }
 
+pub trait Index: Copy {
+    const FORMAT: wgpu::IndexFormat;
+}
+
+impl Index for u16 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint16;
+}
+
+impl Index for u32 {
+    const FORMAT: wgpu::IndexFormat = wgpu::IndexFormat::Uint32;
+}
+
 /*--------------------------------------------------------------------------------------------------*/
 
 pub struct BindingLayoutEntries {
@@ -62,24 +83,413 @@ impl BindingLayoutEntries {
 
 /*--------------------------------------------------------------------------------------------------*/
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EntityId {
+    index: u32,
+    generation: u32,
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+// Reuses the slots of removed values; the generation bump keeps their old ids stale.
+struct SlotMap<T> {
+    slots: Vec<Slot<T>>,
+    free_indices: Vec<u32>,
+}
+
+impl<T> SlotMap<T> {
+    fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free_indices: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, value: T) -> EntityId {
+        match self.free_indices.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.value = Some(value);
+
+                EntityId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+
+                EntityId {
+                    index: (self.slots.len() - 1) as u32,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, id: EntityId) -> Result<T, Error> {
+        self.get(id)?;
+
+        let slot = &mut self.slots[id.index as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_indices.push(id.index);
+
+        Ok(slot.value.take().unwrap())
+    }
+
+    fn get(&self, id: EntityId) -> Result<&T, Error> {
+        match self.slots.get(id.index as usize) {
+            Some(slot) if slot.generation == id.generation => {
+                slot.value.as_ref().ok_or(Error::StaleEntity(id))
+            }
+            _ => Err(Error::StaleEntity(id)),
+        }
+    }
+
+    fn get_mut(&mut self, id: EntityId) -> Result<&mut T, Error> {
+        match self.slots.get_mut(id.index as usize) {
+            Some(slot) if slot.generation == id.generation => {
+                slot.value.as_mut().ok_or(Error::StaleEntity(id))
+            }
+            _ => Err(Error::StaleEntity(id)),
+        }
+    }
+
+    // Indices are only valid while their value is live, as kept by the pipeline draw lists.
+    fn get_by_index(&self, index: u32) -> &T {
+        self.slots[index as usize].value.as_ref().unwrap()
+    }
+
+    fn get_id(&self, index: u32) -> EntityId {
+        EntityId {
+            index,
+            generation: self.slots[index as usize].generation,
+        }
+    }
+
+    fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+}
+
+pub enum DrawOrder {
+    Insertion,
+    FrontToBack,
+    BackToFront,
+    Geometry,
+    Custom(Box<dyn Fn(EntityId, Point3<f32>, Point3<f32>) -> f32>),
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
 pub struct Pipeline {
     handle: wgpu::RenderPipeline,
 
     bind_group_layout: wgpu::BindGroupLayout,
-    entities: std::collections::HashMap<u32, Entity>,
+    entities: SlotMap<Entity>,
+
+    draw_order: DrawOrder,
+    draw_list: Vec<u32>,
+    next_insertion: u64,
+
+    index_format: wgpu::IndexFormat,
+    instance_type: Option<std::any::TypeId>,
 }
 
 impl Pipeline {
-    pub fn new<T: Vertex>(
+    pub fn new<T: Vertex, I: Index>(
+        device: &wgpu::Device,
+        shaders: &Shaders,
+        binding_entries: &BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
+
+        color_state: Option<wgpu::ColorStateDescriptor>,
+        depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
+        rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
+    ) -> Pipeline {
+        let attribute_descriptors = T::get_attribute_descriptors();
+        let vertex_buffer_descriptor = wgpu::VertexBufferDescriptor {
+            stride: std::mem::size_of::<T>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: attribute_descriptors.as_slice(),
+        };
+
+        Self::create::<I>(
+            device,
+            shaders,
+            binding_entries,
+            primitive_topology,
+            &[vertex_buffer_descriptor],
+            color_state,
+            depth_stencil_state,
+            rasterization_state,
+        )
+    }
+
+    pub fn new_instanced<T: Vertex, I: Index, V: Vertex + 'static>(
         device: &wgpu::Device,
         shaders: &Shaders,
         binding_entries: &BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
 
         color_state: Option<wgpu::ColorStateDescriptor>,
         depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
         rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
     ) -> Pipeline {
         let attribute_descriptors = T::get_attribute_descriptors();
+        let instance_attribute_descriptors =
+            V::get_attribute_descriptors_from(attribute_descriptors.len() as wgpu::ShaderLocation);
+
+        let vertex_buffer_descriptors = [
+            wgpu::VertexBufferDescriptor {
+                stride: std::mem::size_of::<T>() as wgpu::BufferAddress,
+                step_mode: wgpu::InputStepMode::Vertex,
+                attributes: attribute_descriptors.as_slice(),
+            },
+            wgpu::VertexBufferDescriptor {
+                stride: std::mem::size_of::<V>() as wgpu::BufferAddress,
+                step_mode: wgpu::InputStepMode::Instance,
+                attributes: instance_attribute_descriptors.as_slice(),
+            },
+        ];
+
+        let mut pipeline = Self::create::<I>(
+            device,
+            shaders,
+            binding_entries,
+            primitive_topology,
+            &vertex_buffer_descriptors,
+            color_state,
+            depth_stencil_state,
+            rasterization_state,
+        );
+        pipeline.instance_type = Some(std::any::TypeId::of::<V>());
+
+        pipeline
+    }
+
+    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        render_pass.set_pipeline(&self.handle);
+        self.draw_list
+            .iter()
+            .map(|index| self.entities.get_by_index(*index))
+            .for_each(|entity| entity.render(render_pass));
+    }
+
+    pub fn set_draw_order(&mut self, draw_order: DrawOrder) -> &mut Self {
+        self.draw_order = draw_order;
+        if let DrawOrder::Insertion = self.draw_order {
+            let entities = &self.entities;
+            self.draw_list
+                .sort_by_key(|index| entities.get_by_index(*index).insertion);
+        }
+
+        self
+    }
+
+    // Entities are placed at the first position reported by their handles (the translation of a
+    // `ShapeHandle`), unless it was overridden with `set_entity_position`.
+    pub fn sort(&mut self, camera_position: Point3<f32>) {
+        let entities = &self.entities;
+        let get_entity = |index: u32| entities.get_by_index(index);
+        let get_distance =
+            |index: u32| (get_entity(index).get_position() - camera_position).magnitude2();
+
+        let mut keyed_list: Vec<(f32, u64, u32)> = match &self.draw_order {
+            DrawOrder::Insertion => return,
+            DrawOrder::Geometry => {
+                self.draw_list.sort_by_key(|index| {
+                    let entity = get_entity(*index);
+                    (entity.geometry_id, entity.insertion)
+                });
+                return;
+            }
+            DrawOrder::FrontToBack => self
+                .draw_list
+                .iter()
+                .map(|index| (get_distance(*index), get_entity(*index).insertion, *index))
+                .collect(),
+            DrawOrder::BackToFront => self
+                .draw_list
+                .iter()
+                .map(|index| (-get_distance(*index), get_entity(*index).insertion, *index))
+                .collect(),
+            DrawOrder::Custom(get_key) => self
+                .draw_list
+                .iter()
+                .map(|index| {
+                    let id = entities.get_id(*index);
+                    let entity = get_entity(*index);
+                    (
+                        get_key(id, entity.get_position(), camera_position),
+                        entity.insertion,
+                        *index,
+                    )
+                })
+                .collect(),
+        };
+
+        keyed_list.sort_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.1.cmp(&b.1))
+        });
+        self.draw_list = keyed_list.into_iter().map(|(_, _, index)| index).collect();
+    }
+
+    // Rebuilds the bind group of every entity whose handles were reallocated since it was
+    // created, such as an instance array that outgrew its capacity.
+    pub fn refresh_bind_groups(&mut self, device: &wgpu::Device) {
+        let bind_group_layout = &self.bind_group_layout;
+        for entity in self.entities.values_mut() {
+            if Self::is_stale(&entity.handles, &entity.binding_generations) {
+                let (bind_group, binding_generations) =
+                    Self::create_entity_bind_group(device, bind_group_layout, &entity.handles);
+                entity.bind_group = bind_group;
+                entity.binding_generations = binding_generations;
+            }
+        }
+    }
+
+    pub fn add_entity(
+        &mut self,
+        device: &wgpu::Device,
+
+        geometry: &Geometry,
+        handles: Vec<SharedBindingHandle>,
+
+        n_instances: u32,
+    ) -> Result<EntityId, Error> {
+        if self.instance_type.is_some() {
+            return Err(Error::Validation(String::from(
+                "Entities of an instanced pipeline need an instance buffer",
+            )));
+        }
+
+        self.insert_entity(device, geometry, handles, n_instances, None)
+    }
+
+    pub fn remove_entity(&mut self, id: EntityId) -> Result<(), Error> {
+        self.entities.remove(id)?;
+        self.draw_list.retain(|index| *index != id.index);
+
+        Ok(())
+    }
+
+    pub fn contains_entity(&self, id: EntityId) -> bool {
+        self.get_entity(id).is_ok()
+    }
+
+    // Overrides the position taken from the entity's handles when sorting.
+    pub fn set_entity_position(
+        &mut self,
+        id: EntityId,
+        position: Point3<f32>,
+    ) -> Result<(), Error> {
+        self.get_entity_mut(id)?.set_position(position);
+
+        Ok(())
+    }
+
+    pub fn get_entity(&self, id: EntityId) -> Result<&Entity, Error> {
+        self.entities.get(id)
+    }
+
+    pub fn get_entity_mut(&mut self, id: EntityId) -> Result<&mut Entity, Error> {
+        self.entities.get_mut(id)
+    }
+
+    pub fn add_instanced_entity<I: Vertex + 'static>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+
+        geometry: &Geometry,
+        handles: Vec<SharedBindingHandle>,
+
+        instances: &[I],
+    ) -> Result<EntityId, Error> {
+        if self.instance_type != Some(std::any::TypeId::of::<I>()) {
+            return Err(Error::Validation(String::from(
+                "Instance type does not match the pipeline's instance layout",
+            )));
+        }
+
+        let instance_buffer = InstanceBuffer::new(device, queue, instances);
+        self.insert_entity(device, geometry, handles, 0, Some(instance_buffer))
+    }
+
+    pub fn add_instance<I: Vertex + 'static>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        id: EntityId,
+        instance: I,
+    ) -> Result<u32, Error> {
+        self.get_instance_buffer_mut(id)?
+            .push(device, queue, instance)
+    }
+
+    pub fn update_instance<I: Vertex + 'static>(
+        &mut self,
+        queue: &wgpu::Queue,
+        id: EntityId,
+        index: u32,
+        instance: I,
+    ) -> Result<(), Error> {
+        self.get_instance_buffer_mut(id)?
+            .update(queue, index, instance)
+    }
+
+    // Moves the last instance into the removed slot and returns its previous index, if it moved.
+    pub fn remove_instance(
+        &mut self,
+        queue: &wgpu::Queue,
+        id: EntityId,
+        index: u32,
+    ) -> Result<Option<u32>, Error> {
+        self.get_instance_buffer_mut(id)?.swap_remove(queue, index)
+    }
+
+    pub fn set_instances<I: Vertex + 'static>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        id: EntityId,
+        instances: &[I],
+    ) -> Result<(), Error> {
+        self.get_instance_buffer_mut(id)?
+            .set(device, queue, instances)
+    }
+
+    pub fn get_instance_count(&self, id: EntityId) -> Result<u32, Error> {
+        Ok(self.get_entity(id)?.get_instance_count())
+    }
+
+    /*-------------------------------------------------*/
+
+    #[allow(clippy::too_many_arguments)]
+    fn create<I: Index>(
+        device: &wgpu::Device,
+        shaders: &Shaders,
+        binding_entries: &BindingLayoutEntries,
+        primitive_topology: wgpu::PrimitiveTopology,
+        vertex_buffer_descriptors: &[wgpu::VertexBufferDescriptor],
+
+        color_state: Option<wgpu::ColorStateDescriptor>,
+        depth_stencil_state: Option<wgpu::DepthStencilStateDescriptor>,
+        rasterization_state: Option<wgpu::RasterizationStateDescriptor>,
+    ) -> Pipeline {
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: None,
             entries: binding_entries.entries.as_slice(),
@@ -91,12 +501,6 @@ impl Pipeline {
             push_constant_ranges: &[],
         });
 
-        let vertex_buffer_descriptor = wgpu::VertexBufferDescriptor {
-            stride: std::mem::size_of::<T>() as wgpu::BufferAddress,
-            step_mode: wgpu::InputStepMode::Vertex,
-            attributes: attribute_descriptors.as_slice(),
-        };
-
         let color_states_vec: Vec<wgpu::ColorStateDescriptor> = match color_state {
             None => vec![],
             Some(desc) => vec![desc],
@@ -114,12 +518,12 @@ impl Pipeline {
                 entry_point: "main",
             }),
             rasterization_state,
-            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            primitive_topology,
             color_states: color_states_vec.as_slice(),
             depth_stencil_state,
             vertex_state: wgpu::VertexStateDescriptor {
-                index_format: wgpu::IndexFormat::Uint16,
-                vertex_buffers: &[vertex_buffer_descriptor],
+                index_format: I::FORMAT,
+                vertex_buffers: vertex_buffer_descriptors,
             },
             sample_count: 1,
             sample_mask: 0,
@@ -130,52 +534,64 @@ impl Pipeline {
             handle,
 
             bind_group_layout,
-            entities: std::collections::HashMap::new(),
-        }
-    }
+            entities: SlotMap::new(),
 
-    pub fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
-        render_pass.set_pipeline(&self.handle);
-        self.entities
-            .iter()
-            .for_each(|(_, entity)| entity.render(render_pass));
+            draw_order: DrawOrder::Insertion,
+            draw_list: Vec::new(),
+            next_insertion: 0,
+
+            index_format: I::FORMAT,
+            instance_type: None,
+        }
     }
 
-    pub fn add_entity(
+    fn insert_entity(
         &mut self,
         device: &wgpu::Device,
 
         geometry: &Geometry,
-        handles: Vec<&dyn BindingHandle>,
+        handles: Vec<SharedBindingHandle>,
 
         n_instances: u32,
-    ) -> u32 {
-        let bind_group = self.create_bind_group(device, handles);
-        let id = self.find_new_id();
+        instance_buffer: Option<InstanceBuffer>,
+    ) -> Result<EntityId, Error> {
+        if let DrawRange::Indexed { index_format, .. } = &geometry.draw_range {
+            if *index_format != self.index_format {
+                return Err(Error::Validation(format!(
+                    "Geometry index format {:?} does not match the pipeline's index format {:?}",
+                    index_format, self.index_format
+                )));
+            }
+        }
 
-        self.entities.insert(id, Entity {
+        let (bind_group, binding_generations) =
+            Self::create_entity_bind_group(device, &self.bind_group_layout, &handles);
+        let entity = Entity {
             vertex_buffer: std::rc::Rc::clone(&geometry.vertex_buffer),
-            index_buffer: std::rc::Rc::clone(&geometry.index_buffer),
-
-            n_indices: geometry.n_indices,
+            draw_range: geometry.draw_range.clone(),
+            geometry_id: geometry.id,
 
             n_instances,
+            instance_buffer,
 
+            handles,
             bind_group,
-        });
+            binding_generations,
 
-        id
-    }
+            insertion: self.next_insertion,
+            position: None,
+        };
+        self.next_insertion += 1;
 
-    pub fn remove_entity(&mut self, id: u32) {
-        self.entities.remove(&id);
-    }
+        let id = self.entities.insert(entity);
+        self.draw_list.push(id.index);
 
-    /*-------------------------------------------------*/
+        Ok(id)
+    }
 
     fn create_bind_group(
-        &self,
         device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
         handles: Vec<&dyn BindingHandle>,
     ) -> wgpu::BindGroup {
         let entries: Vec<wgpu::BindGroupEntry> = handles
@@ -190,61 +606,576 @@ impl Pipeline {
 
         device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: None,
-            layout: &self.bind_group_layout,
+            layout: bind_group_layout,
             entries: entries.as_slice(),
         })
     }
 
-    fn find_new_id(&self) -> u32 {
-        let mut id = 0;
-        loop {
-            if self.entities.contains_key(&id) {
-                id += 1;
+    fn create_entity_bind_group(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        handles: &[SharedBindingHandle],
+    ) -> (wgpu::BindGroup, Vec<u32>) {
+        let borrowed_handles: Vec<std::cell::Ref<dyn BindingHandle>> =
+            handles.iter().map(|handle| handle.borrow()).collect();
+        let handle_refs: Vec<&dyn BindingHandle> =
+            borrowed_handles.iter().map(|handle| &**handle).collect();
+
+        let binding_generations = Self::get_binding_generations(&handle_refs);
+        (
+            Self::create_bind_group(device, bind_group_layout, handle_refs),
+            binding_generations,
+        )
+    }
+
+    fn get_binding_generations(handles: &[&dyn BindingHandle]) -> Vec<u32> {
+        handles
+            .iter()
+            .map(|handle| handle.get_binding().get_generation())
+            .collect()
+    }
+
+    fn is_stale(handles: &[SharedBindingHandle], binding_generations: &[u32]) -> bool {
+        handles
+            .iter()
+            .zip(binding_generations.iter())
+            .any(|(handle, generation)| {
+                handle.borrow().get_binding().get_generation() != *generation
+            })
+    }
+
+    fn get_instance_buffer_mut(&mut self, id: EntityId) -> Result<&mut InstanceBuffer, Error> {
+        self.get_entity_mut(id)?
+            .instance_buffer
+            .as_mut()
+            .ok_or_else(|| Error::Validation(format!("Entity {:?} is not instanced", id)))
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+struct Dispatch {
+    handles: Vec<SharedBindingHandle>,
+    bind_group: wgpu::BindGroup,
+    binding_generations: Vec<u32>,
+
+    workgroups: [u32; 3],
+}
+
+pub struct ComputePipeline {
+    handle: wgpu::ComputePipeline,
+
+    bind_group_layout: wgpu::BindGroupLayout,
+    dispatches: SlotMap<Dispatch>,
+}
+
+impl ComputePipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        compute_module: &wgpu::ShaderModule,
+        binding_entries: &BindingLayoutEntries,
+    ) -> ComputePipeline {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: binding_entries.entries.as_slice(),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: None,
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let handle = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(&pipeline_layout),
+            compute_stage: wgpu::ProgrammableStageDescriptor {
+                module: compute_module,
+                entry_point: "main",
+            },
+        });
+
+        ComputePipeline {
+            handle,
+
+            bind_group_layout,
+            dispatches: SlotMap::new(),
+        }
+    }
+
+    pub fn compute<'a>(&'a self, compute_pass: &mut wgpu::ComputePass<'a>) {
+        compute_pass.set_pipeline(&self.handle);
+        for dispatch in self.dispatches.values() {
+            let [x, y, z] = dispatch.workgroups;
+            if x == 0 || y == 0 || z == 0 {
+                continue;
             }
-            else {
-                break id;
+
+            compute_pass.set_bind_group(0, &dispatch.bind_group, &[]);
+            compute_pass.dispatch(x, y, z);
+        }
+    }
+
+    pub fn add_dispatch(
+        &mut self,
+        device: &wgpu::Device,
+        handles: Vec<SharedBindingHandle>,
+        workgroups: [u32; 3],
+    ) -> EntityId {
+        let (bind_group, binding_generations) =
+            Pipeline::create_entity_bind_group(device, &self.bind_group_layout, &handles);
+
+        self.dispatches.insert(Dispatch {
+            handles,
+            bind_group,
+            binding_generations,
+
+            workgroups,
+        })
+    }
+
+    pub fn set_workgroups(&mut self, id: EntityId, workgroups: [u32; 3]) -> Result<(), Error> {
+        self.dispatches.get_mut(id)?.workgroups = workgroups;
+
+        Ok(())
+    }
+
+    // Same as `Pipeline::refresh_bind_groups`, called through `Renderer::refresh_bind_groups`.
+    pub fn refresh_bind_groups(&mut self, device: &wgpu::Device) {
+        let bind_group_layout = &self.bind_group_layout;
+        for dispatch in self.dispatches.values_mut() {
+            if Pipeline::is_stale(&dispatch.handles, &dispatch.binding_generations) {
+                let (bind_group, binding_generations) = Pipeline::create_entity_bind_group(
+                    device,
+                    bind_group_layout,
+                    &dispatch.handles,
+                );
+                dispatch.bind_group = bind_group;
+                dispatch.binding_generations = binding_generations;
             }
         }
     }
+
+    pub fn contains_dispatch(&self, id: EntityId) -> bool {
+        self.dispatches.get(id).is_ok()
+    }
+
+    pub fn remove_dispatch(&mut self, id: EntityId) -> Result<(), Error> {
+        self.dispatches.remove(id)?;
+
+        Ok(())
+    }
 }
 
 /*--------------------------------------------------------------------------------------------------*/
 
+static NEXT_GEOMETRY_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+#[derive(Clone)]
+enum DrawRange {
+    Indexed {
+        index_buffer: std::rc::Rc<wgpu::Buffer>,
+        index_format: wgpu::IndexFormat,
+        n_indices: u32,
+    },
+    NonIndexed {
+        n_vertices: u32,
+    },
+}
+
 pub struct Geometry {
-    vertex_buffer: std::rc::Rc<wgpu::Buffer>,
-    index_buffer: std::rc::Rc<wgpu::Buffer>,
+    id: u32,
 
-    n_indices: u32,
+    vertex_buffer: std::rc::Rc<wgpu::Buffer>,
+    draw_range: DrawRange,
 }
 
 impl Geometry {
-    pub fn new(vertex_buffer: wgpu::Buffer, index_buffer: wgpu::Buffer, n_indices: u32) -> Self {
+    pub fn new<I: Index>(
+        vertex_buffer: wgpu::Buffer,
+        index_buffer: wgpu::Buffer,
+        n_indices: u32,
+    ) -> Self {
+        Self::create(
+            vertex_buffer,
+            DrawRange::Indexed {
+                index_buffer: std::rc::Rc::new(index_buffer),
+                index_format: I::FORMAT,
+                n_indices,
+            },
+        )
+    }
+
+    pub fn new_non_indexed(vertex_buffer: wgpu::Buffer, n_vertices: u32) -> Self {
+        Self::create(vertex_buffer, DrawRange::NonIndexed { n_vertices })
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_index_format(&self) -> Option<wgpu::IndexFormat> {
+        match &self.draw_range {
+            DrawRange::Indexed { index_format, .. } => Some(*index_format),
+            DrawRange::NonIndexed { .. } => None,
+        }
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.get_index_format().is_some()
+    }
+
+    fn create(vertex_buffer: wgpu::Buffer, draw_range: DrawRange) -> Self {
         Self {
+            id: NEXT_GEOMETRY_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+
             vertex_buffer: std::rc::Rc::new(vertex_buffer),
-            index_buffer: std::rc::Rc::new(index_buffer),
-            n_indices,
+            draw_range,
         }
     }
 }
 
 /*--------------------------------------------------------------------------------------------------*/
 
-struct Entity {
+pub struct Entity {
     vertex_buffer: std::rc::Rc<wgpu::Buffer>,
-    index_buffer: std::rc::Rc<wgpu::Buffer>,
+    draw_range: DrawRange,
+    geometry_id: u32,
 
-    n_indices: u32,
     n_instances: u32,
+    instance_buffer: Option<InstanceBuffer>,
 
+    handles: Vec<SharedBindingHandle>,
     bind_group: wgpu::BindGroup,
+    binding_generations: Vec<u32>,
+
+    insertion: u64,
+    position: Option<Point3<f32>>,
 }
 
 impl Entity {
+    pub fn get_geometry_id(&self) -> u32 {
+        self.geometry_id
+    }
+
+    pub fn is_instanced(&self) -> bool {
+        self.instance_buffer.is_some()
+    }
+
+    pub fn get_instance_count(&self) -> u32 {
+        match &self.instance_buffer {
+            Some(instance_buffer) => instance_buffer.len(),
+            None => self.n_instances,
+        }
+    }
+
+    pub fn get_position(&self) -> Point3<f32> {
+        self.position
+            .or_else(|| {
+                self.handles
+                    .iter()
+                    .find_map(|handle| handle.borrow().get_position())
+            })
+            .unwrap_or_else(|| Point3::new(0.0, 0.0, 0.0))
+    }
+
+    pub fn set_position(&mut self, position: Point3<f32>) -> &mut Self {
+        self.position = Some(position);
+
+        self
+    }
+
+    /*-------------------------------------------------*/
+
     fn render<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        let n_instances = self.get_instance_count();
+        if n_instances == 0 {
+            return;
+        }
+
         render_pass.set_bind_group(0, &self.bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..));
+        if let Some(instance_buffer) = &self.instance_buffer {
+            render_pass.set_vertex_buffer(1, instance_buffer.buffer.slice(..));
+        }
+
+        match &self.draw_range {
+            DrawRange::Indexed {
+                index_buffer,
+                n_indices,
+                ..
+            } => {
+                render_pass.set_index_buffer(index_buffer.slice(..));
+                render_pass.draw_indexed(0..*n_indices, 0, 0..n_instances);
+            }
+            DrawRange::NonIndexed { n_vertices } => {
+                render_pass.draw(0..*n_vertices, 0..n_instances);
+            }
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+struct InstanceBuffer {
+    instance_type: std::any::TypeId,
+    stride: usize,
+
+    data: Vec<u8>,
+    len: u32,
+    capacity: u32,
+    buffer: wgpu::Buffer,
+}
+
+impl InstanceBuffer {
+    fn new<I: 'static>(device: &wgpu::Device, queue: &wgpu::Queue, instances: &[I]) -> Self {
+        let stride = std::mem::size_of::<I>();
+        let capacity = (instances.len() as u32).max(1);
+
+        let instance_buffer = Self {
+            instance_type: std::any::TypeId::of::<I>(),
+            stride,
+
+            data: Self::as_bytes(instances).to_vec(),
+            len: instances.len() as u32,
+            capacity,
+            buffer: Self::create_buffer(device, stride, capacity),
+        };
+        instance_buffer.write(queue, 0, instance_buffer.len());
+
+        instance_buffer
+    }
+
+    fn len(&self) -> u32 {
+        self.len
+    }
+
+    fn push<I: 'static>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        instance: I,
+    ) -> Result<u32, Error> {
+        self.check_type::<I>()?;
+
+        let index = self.len;
+        self.data
+            .extend_from_slice(Self::as_bytes(std::slice::from_ref(&instance)));
+        self.len += 1;
+        if !self.reserve(device, queue) {
+            self.write(queue, index, 1);
+        }
+
+        Ok(index)
+    }
+
+    fn update<I: 'static>(
+        &mut self,
+        queue: &wgpu::Queue,
+        index: u32,
+        instance: I,
+    ) -> Result<(), Error> {
+        self.check_type::<I>()?;
+        self.check_index(index)?;
+
+        let offset = index as usize * self.stride;
+        self.data[offset..offset + self.stride]
+            .copy_from_slice(Self::as_bytes(std::slice::from_ref(&instance)));
+        self.write(queue, index, 1);
+
+        Ok(())
+    }
+
+    fn swap_remove(&mut self, queue: &wgpu::Queue, index: u32) -> Result<Option<u32>, Error> {
+        self.check_index(index)?;
+
+        let last = self.len - 1;
+        let offset = index as usize * self.stride;
+        let last_offset = last as usize * self.stride;
+        self.data
+            .copy_within(last_offset..last_offset + self.stride, offset);
+        self.data.truncate(last_offset);
+        self.len = last;
+
+        if index == last {
+            return Ok(None);
+        }
+        self.write(queue, index, 1);
+
+        Ok(Some(last))
+    }
+
+    fn set<I: 'static>(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        instances: &[I],
+    ) -> Result<(), Error> {
+        self.check_type::<I>()?;
+
+        self.data = Self::as_bytes(instances).to_vec();
+        self.len = instances.len() as u32;
+        if !self.reserve(device, queue) {
+            self.write(queue, 0, self.len());
+        }
+
+        Ok(())
+    }
+
+    /*-------------------------------------------------*/
+
+    // Returns true if the buffer was reallocated, in which case all data has been uploaded.
+    fn reserve(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) -> bool {
+        let len = self.len();
+        if len <= self.capacity {
+            return false;
+        }
+
+        self.capacity = len.next_power_of_two();
+        self.buffer = Self::create_buffer(device, self.stride, self.capacity);
+        self.write(queue, 0, len);
+
+        true
+    }
+
+    fn write(&self, queue: &wgpu::Queue, first_index: u32, count: u32) {
+        if count == 0 || self.stride == 0 {
+            return;
+        }
+
+        let start = first_index as usize * self.stride;
+        let end = start + count as usize * self.stride;
+        queue.write_buffer(
+            &self.buffer,
+            start as wgpu::BufferAddress,
+            &self.data[start..end],
+        );
+    }
+
+    fn check_type<I: 'static>(&self) -> Result<(), Error> {
+        if self.instance_type != std::any::TypeId::of::<I>() {
+            return Err(Error::Validation(String::from(
+                "Instance type does not match the entity's instance buffer",
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn check_index(&self, index: u32) -> Result<(), Error> {
+        if index >= self.len() {
+            return Err(Error::Validation(format!(
+                "Instance index {} is out of range ({} instances)",
+                index,
+                self.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn create_buffer(device: &wgpu::Device, stride: usize, capacity: u32) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (stride * capacity as usize) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    fn as_bytes<I>(instances: &[I]) -> &[u8] {
+        unsafe {
+            std::slice::from_raw_parts(
+                instances.as_ptr() as *const u8,
+                instances.len() * std::mem::size_of::<I>(),
+            )
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_map_reuses_slots_with_new_generations() {
+        let mut slots = SlotMap::new();
+        let first = slots.insert("first");
+        let second = slots.insert("second");
+        assert_eq!(*slots.get(first).unwrap(), "first");
+        assert_eq!(*slots.get(second).unwrap(), "second");
+
+        assert_eq!(slots.remove(first).unwrap(), "first");
+        assert!(matches!(slots.get(first), Err(Error::StaleEntity(id)) if id == first));
+        assert!(slots.remove(first).is_err());
+
+        let third = slots.insert("third");
+        assert_eq!(third.index, first.index);
+        assert_ne!(third.generation, first.generation);
+        assert!(slots.get(first).is_err());
+        assert_eq!(*slots.get(third).unwrap(), "third");
+        assert_eq!(slots.get_id(third.index), third);
+        assert_eq!(*slots.get_by_index(second.index), "second");
+
+        *slots.get_mut(second).unwrap() = "changed";
+        let mut values: Vec<&str> = slots.values().cloned().collect();
+        values.sort();
+        assert_eq!(values, ["changed", "third"]);
+    }
+
+    #[test]
+    fn slot_map_rejects_unknown_ids() {
+        let mut slots = SlotMap::<u32>::new();
+        let id = EntityId {
+            index: 3,
+            generation: 0,
+        };
+
+        assert!(slots.get(id).is_err());
+        assert!(slots.get_mut(id).is_err());
+        assert!(slots.remove(id).is_err());
+    }
+
+    #[test]
+    fn slot_map_ids_stay_distinct_across_reuse() {
+        let mut slots = SlotMap::new();
+        let mut live = Vec::new();
+        let mut stale = Vec::new();
+
+        for round in 0..4u32 {
+            for value in 0..3u32 {
+                let value = round * 3 + value;
+                live.push((slots.insert(value), value));
+            }
+
+            // Removes every other live value, so later inserts reuse slots of different ages.
+            let mut index = 0;
+            live.retain(|&(id, _)| {
+                index += 1;
+                if index % 2 == 0 {
+                    slots.remove(id).unwrap();
+                    stale.push(id);
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        for (id, value) in live.iter() {
+            assert_eq!(slots.get(*id).unwrap(), value);
+            assert!(!stale.contains(id));
+        }
+        for id in stale.iter() {
+            assert!(slots.get(*id).is_err());
+        }
 
-        render_pass.draw_indexed(0..self.n_indices, 0, 0..self.n_instances);
+        let mut ids: Vec<EntityId> = live.iter().map(|&(id, _)| id).chain(stale).collect();
+        let count = ids.len();
+        ids.sort_by_key(|id| (id.index, id.generation));
+        ids.dedup();
+        assert_eq!(ids.len(), count);
     }
 }
@@ -1,26 +1,48 @@
+use std::{
+    cell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut},
+    rc::Rc,
+};
+
 pub struct RcMut<T> {
-    obj: std::rc::Rc<Box<T>>,
+    obj: Rc<RefCell<T>>,
 }
 
 impl<T> RcMut<T> {
     pub fn new(obj: T) -> Self {
         Self {
-            obj: std::rc::Rc::new(Box::new(obj)),
+            obj: Rc::new(RefCell::new(obj)),
         }
     }
-}
 
-impl<T> std::ops::Deref for RcMut<T> {
-    type Target = T;
+    pub fn borrow(&self) -> Ref<T> {
+        self.obj.borrow()
+    }
+
+    pub fn borrow_mut(&self) -> RefMut<T> {
+        self.obj.borrow_mut()
+    }
+
+    pub fn try_borrow(&self) -> Result<Ref<T>, BorrowError> {
+        self.obj.try_borrow()
+    }
 
-    fn deref(&self) -> &Self::Target {
-        self.obj.as_ref().as_ref()
+    pub fn try_borrow_mut(&self) -> Result<RefMut<T>, BorrowMutError> {
+        self.obj.try_borrow_mut()
+    }
+
+    pub fn to_rc(&self) -> Rc<RefCell<T>> {
+        Rc::clone(&self.obj)
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.obj, &other.obj)
     }
 }
 
-impl<T> std::ops::DerefMut for RcMut<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        let reference: &T = self.obj.as_ref().as_ref();
-        unsafe { (reference as *const T as *mut T).as_mut().unwrap() }
+impl<T> Clone for RcMut<T> {
+    fn clone(&self) -> Self {
+        Self {
+            obj: Rc::clone(&self.obj),
+        }
     }
 }
@@ -1,11 +1,88 @@
-use crate::pipeline::Pipeline;
+use crate::{mipmap::MipmapTarget, pipeline::Pipeline};
 use wgpu;
 
 pub enum AttachmentView {
     Dynamic,
     Static(wgpu::TextureView),
+    Sized(SizedView),
 }
 
+impl AttachmentView {
+    fn resize(&mut self, device: &wgpu::Device, size: winit::dpi::PhysicalSize<u32>) {
+        if let AttachmentView::Sized(sized_view) = self {
+            sized_view.resize(device, size);
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct SizedView {
+    format: wgpu::TextureFormat,
+    usage: wgpu::TextureUsage,
+
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl SizedView {
+    pub fn new(
+        device: &wgpu::Device,
+        size: winit::dpi::PhysicalSize<u32>,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsage,
+    ) -> Self {
+        let texture = Self::create_texture(device, size, format, usage);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self {
+            format,
+            usage,
+
+            texture,
+            view,
+        }
+    }
+
+    pub fn get_texture(&self) -> &wgpu::Texture {
+        &self.texture
+    }
+
+    pub fn get_view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    fn resize(&mut self, device: &wgpu::Device, size: winit::dpi::PhysicalSize<u32>) {
+        self.texture = Self::create_texture(device, size, self.format, self.usage);
+        self.view = self
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+    }
+
+    fn create_texture(
+        device: &wgpu::Device,
+        size: winit::dpi::PhysicalSize<u32>,
+        format: wgpu::TextureFormat,
+        usage: wgpu::TextureUsage,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+
+            format,
+            usage,
+        })
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
 struct Attachment<T> {
     view: AttachmentView,
     ops: wgpu::Operations<T>,
@@ -16,6 +93,7 @@ pub struct RenderPass {
     depth_attachment: Option<Attachment<f32>>,
 
     pipelines: Vec<Pipeline>,
+    mipmap_targets: Vec<MipmapTarget>,
 }
 
 impl RenderPass {
@@ -25,6 +103,7 @@ impl RenderPass {
             depth_attachment: None,
 
             pipelines: Vec::new(),
+            mipmap_targets: Vec::new(),
         }
     }
 
@@ -43,11 +122,11 @@ impl RenderPass {
 
     pub fn set_depth_attachment(
         &mut self,
-        depth_view: wgpu::TextureView,
+        depth_view: AttachmentView,
         depth_ops: wgpu::Operations<f32>,
     ) -> &mut Self {
         self.depth_attachment = Some(Attachment {
-            view: AttachmentView::Static(depth_view),
+            view: depth_view,
             ops: depth_ops,
         });
 
@@ -60,11 +139,39 @@ impl RenderPass {
         (self.pipelines.len() - 1) as u32
     }
 
+    // Regenerates the mip chain of a texture this pass renders into, after every submit.
+    pub fn add_mipmap_target(&mut self, target: MipmapTarget) -> &mut Self {
+        self.mipmap_targets.push(target);
+
+        self
+    }
+
     pub fn get_pipeline(&mut self, id: u32) -> &mut Pipeline {
         &mut self.pipelines[id as usize]
     }
 
-    pub fn submit(&self, encoder: &mut wgpu::CommandEncoder, frame: &wgpu::SwapChainFrame) {
+    pub fn sort(&mut self, camera_position: cgmath::Point3<f32>) {
+        self.pipelines
+            .iter_mut()
+            .for_each(|pipeline| pipeline.sort(camera_position));
+    }
+
+    pub fn refresh_bind_groups(&mut self, device: &wgpu::Device) {
+        self.pipelines
+            .iter_mut()
+            .for_each(|pipeline| pipeline.refresh_bind_groups(device));
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, size: winit::dpi::PhysicalSize<u32>) {
+        if let Some(attachment) = &mut self.color_attachment {
+            attachment.view.resize(device, size);
+        }
+        if let Some(attachment) = &mut self.depth_attachment {
+            attachment.view.resize(device, size);
+        }
+    }
+
+    pub fn submit(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
         let depth_attachment_descriptor = match &self.depth_attachment {
             None => None,
             Some(attachment) => match &attachment.view {
@@ -76,14 +183,22 @@ impl RenderPass {
                         stencil_ops: None,
                     })
                 }
+                AttachmentView::Sized(sized_view) => {
+                    Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                        attachment: sized_view.get_view(),
+                        depth_ops: Some(attachment.ops),
+                        stencil_ops: None,
+                    })
+                }
             },
         };
 
         let mut rpass = match &self.color_attachment {
             Some(color_attachment) => {
                 let color_attachment_view: &wgpu::TextureView = match &color_attachment.view {
-                    AttachmentView::Dynamic => &frame.output.view,
+                    AttachmentView::Dynamic => target,
                     AttachmentView::Static(view) => view,
+                    AttachmentView::Sized(sized_view) => sized_view.get_view(),
                 };
                 encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                     color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
@@ -103,5 +218,10 @@ impl RenderPass {
         for p in self.pipelines.iter() {
             p.render(&mut rpass)
         }
+        drop(rpass);
+
+        for target in self.mipmap_targets.iter() {
+            target.encode(encoder);
+        }
     }
 }
@@ -1,15 +1,21 @@
-use crate::render_pass::RenderPass;
+use crate::{
+    compute_pass::{ComputePass, ComputeSchedule},
+    error::Error,
+    render_pass::RenderPass,
+};
 
 use wgpu;
 
 pub struct Renderer {
     render_passes: Vec<RenderPass>,
+    compute_passes: Vec<(ComputePass, ComputeSchedule)>,
 }
 
 impl Renderer {
     pub fn new() -> Self {
         Self {
             render_passes: Vec::new(),
+            compute_passes: Vec::new(),
         }
     }
 
@@ -23,9 +29,139 @@ impl Renderer {
         &mut self.render_passes[id as usize]
     }
 
-    pub fn submit(&self, encoder: &mut wgpu::CommandEncoder, frame: &wgpu::SwapChainFrame) {
+    // Passes scheduled around a render pass need that pass to be added first.
+    pub fn add_compute_pass(
+        &mut self,
+        cpass: ComputePass,
+        schedule: ComputeSchedule,
+    ) -> Result<u32, Error> {
+        match schedule {
+            ComputeSchedule::BeforeRenderPass(id) | ComputeSchedule::AfterRenderPass(id)
+                if id as usize >= self.render_passes.len() =>
+            {
+                return Err(Error::Validation(format!(
+                    "Compute pass scheduled around render pass {}, but only {} were added",
+                    id,
+                    self.render_passes.len()
+                )));
+            }
+            _ => {}
+        }
+
+        self.compute_passes.push((cpass, schedule));
+
+        Ok((self.compute_passes.len() - 1) as u32)
+    }
+
+    pub fn get_compute_pass(&mut self, id: u32) -> &mut ComputePass {
+        &mut self.compute_passes[id as usize].0
+    }
+
+    // Sorts every pass from the same eye; passes rendered from another camera, such as shadow
+    // passes, should be sorted on their own with `sort_render_pass`.
+    pub fn sort(&mut self, camera_position: cgmath::Point3<f32>) {
+        self.render_passes
+            .iter_mut()
+            .for_each(|rpass| rpass.sort(camera_position));
+    }
+
+    pub fn sort_render_pass(&mut self, id: u32, camera_position: cgmath::Point3<f32>) {
+        self.render_passes[id as usize].sort(camera_position);
+    }
+
+    // Called by `GraphicsManager::render` before submitting.
+    pub fn refresh_bind_groups(&mut self, device: &wgpu::Device) {
         self.render_passes
+            .iter_mut()
+            .for_each(|rpass| rpass.refresh_bind_groups(device));
+        self.compute_passes
+            .iter_mut()
+            .for_each(|(cpass, _)| cpass.refresh_bind_groups(device));
+    }
+
+    pub fn resize(&mut self, device: &wgpu::Device, size: winit::dpi::PhysicalSize<u32>) {
+        self.render_passes
+            .iter_mut()
+            .for_each(|rpass| rpass.resize(device, size));
+    }
+
+    pub fn submit(&self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        self.submit_compute(encoder, ComputeSchedule::BeforeRendering);
+        for (id, rpass) in self.render_passes.iter().enumerate() {
+            self.submit_compute(encoder, ComputeSchedule::BeforeRenderPass(id as u32));
+            rpass.submit(encoder, target);
+            self.submit_compute(encoder, ComputeSchedule::AfterRenderPass(id as u32));
+        }
+        self.submit_compute(encoder, ComputeSchedule::AfterRendering);
+    }
+
+    /*-------------------------------------------------*/
+
+    fn submit_compute(&self, encoder: &mut wgpu::CommandEncoder, schedule: ComputeSchedule) {
+        self.compute_passes
             .iter()
-            .for_each(|rpass| rpass.submit(encoder, frame));
+            .filter(|(_, cpass_schedule)| *cpass_schedule == schedule)
+            .for_each(|(cpass, _)| cpass.submit(encoder));
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_passes_need_their_render_pass() {
+        let mut renderer = Renderer::new();
+        assert!(renderer
+            .add_compute_pass(ComputePass::new(), ComputeSchedule::BeforeRenderPass(0))
+            .is_err());
+        assert_eq!(
+            renderer
+                .add_compute_pass(ComputePass::new(), ComputeSchedule::BeforeRendering)
+                .unwrap(),
+            0
+        );
+
+        let id = renderer.add_render_pass(RenderPass::new());
+        assert_eq!(
+            renderer
+                .add_compute_pass(ComputePass::new(), ComputeSchedule::BeforeRenderPass(id))
+                .unwrap(),
+            1
+        );
+        assert!(renderer
+            .add_compute_pass(ComputePass::new(), ComputeSchedule::AfterRenderPass(id + 1))
+            .is_err());
+    }
+
+    #[test]
+    fn compute_passes_accept_every_schedule() {
+        let mut renderer = Renderer::new();
+        let first = renderer.add_render_pass(RenderPass::new());
+        let second = renderer.add_render_pass(RenderPass::new());
+
+        let schedules = [
+            ComputeSchedule::BeforeRendering,
+            ComputeSchedule::AfterRendering,
+            ComputeSchedule::BeforeRenderPass(first),
+            ComputeSchedule::AfterRenderPass(first),
+            ComputeSchedule::BeforeRenderPass(second),
+            ComputeSchedule::AfterRenderPass(second),
+        ];
+        for (id, schedule) in schedules.iter().enumerate() {
+            assert_eq!(
+                renderer
+                    .add_compute_pass(ComputePass::new(), *schedule)
+                    .unwrap(),
+                id as u32
+            );
+        }
+
+        assert!(renderer
+            .add_compute_pass(ComputePass::new(), ComputeSchedule::BeforeRenderPass(2))
+            .is_err());
+        assert_eq!(renderer.compute_passes.len(), schedules.len());
     }
 }
@@ -1,13 +1,74 @@
-use super::application::{Application, ApplicationController};
+use super::{
+    application::{Application, ApplicationController},
+    clock::{Clock, ManualClock, SystemClock},
+    config::GraphicsConfig,
+    error::Error,
+};
+
+use std::rc::Rc;
+
+pub enum TimeStep {
+    Variable,
+    // Steps the controller by `delta` milliseconds (> 0), at most `max_steps` (>= 1) times per
+    // redraw; time beyond that is dropped instead of being caught up later.
+    Fixed { delta: f32, max_steps: u32 },
+}
+
+impl TimeStep {
+    pub fn validate(&self) -> Result<(), Error> {
+        match *self {
+            TimeStep::Variable => Ok(()),
+            TimeStep::Fixed { delta, .. } if !(delta.is_finite() && delta > 0.0) => {
+                Err(Error::Validation(format!(
+                    "Fixed time step must be positive, got {} ms",
+                    delta
+                )))
+            }
+            TimeStep::Fixed { max_steps: 0, .. } => Err(Error::Validation(String::from(
+                "Fixed time step needs at least one step per redraw",
+            ))),
+            TimeStep::Fixed { .. } => Ok(()),
+        }
+    }
+}
+
+pub fn run<A: Application + 'static, C: ApplicationController<A> + 'static>(
+    fps: u32,
+    config: GraphicsConfig,
+) {
+    run_with_timestep::<A, C>(fps, TimeStep::Variable, config);
+}
+
+pub fn run_with_timestep<A: Application + 'static, C: ApplicationController<A> + 'static>(
+    fps: u32,
+    timestep_mode: TimeStep,
+    config: GraphicsConfig,
+) {
+    run_with_clock::<A, C, SystemClock>(fps, timestep_mode, SystemClock::new(), config);
+}
+
+pub fn run_with_clock<
+    A: Application + 'static,
+    C: ApplicationController<A> + 'static,
+    K: Clock + 'static,
+>(
+    fps: u32,
+    timestep_mode: TimeStep,
+    clock: K,
+    config: GraphicsConfig,
+) {
+    timestep_mode
+        .validate()
+        .unwrap_or_else(|err| panic!("{}", err));
 
-pub fn run<A: Application + 'static, C: ApplicationController<A> + 'static>(fps: u32) {
     let event_loop = winit::event_loop::EventLoop::new();
 
-    let mut app = Box::new(A::new(&event_loop));
+    let mut app = Box::new(A::new(Some(&event_loop), &config));
     let mut controller = C::new(&mut app);
 
-    let mut redraw_handler = RedrawHandler::new(fps);
-    let mut timestep = TimeStepSupplier::new();
+    let clock = Rc::new(clock);
+    let mut redraw_handler = RedrawHandler::new(fps, Rc::clone(&clock));
+    let mut frame_stepper = FrameStepper::new(&timestep_mode, clock);
     event_loop.run(move |event, _, control_flow| {
         let _ = &app;
 
@@ -22,6 +83,12 @@ pub fn run<A: Application + 'static, C: ApplicationController<A> + 'static>(fps:
                 winit::event::WindowEvent::CloseRequested => {
                     *control_flow = winit::event_loop::ControlFlow::Exit;
                 }
+                winit::event::WindowEvent::Resized(size) => {
+                    app.resize(size);
+                }
+                winit::event::WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    app.resize(*new_inner_size);
+                }
                 _ => {
                     app.handle_input(&event);
                 }
@@ -30,14 +97,67 @@ pub fn run<A: Application + 'static, C: ApplicationController<A> + 'static>(fps:
                 println!("EVENT [{:?}]", event);
             }
             winit::event::Event::RedrawRequested(_) => {
-                controller.step(app.as_mut(), timestep.step());
-                app.step();
+                frame_stepper.step(app.as_mut(), &mut controller);
             }
             _ => {}
         }
     });
 }
 
+pub fn run_headless<A: Application, C: ApplicationController<A>>(
+    frames: u32,
+    time_step: f32,
+    config: GraphicsConfig,
+) -> A {
+    run_headless_with_input::<A, C>(frames, time_step, TimeStep::Variable, config, |_| {
+        Vec::new()
+    })
+}
+
+// Advances a manual clock by `time_step` milliseconds before every frame.
+pub fn run_headless_with_input<A: Application, C: ApplicationController<A>>(
+    frames: u32,
+    time_step: f32,
+    timestep_mode: TimeStep,
+    config: GraphicsConfig,
+    mut input: impl FnMut(u32) -> Vec<winit::event::WindowEvent<'static>>,
+) -> A {
+    let clock = ManualClock::new();
+    let frame_clock = clock.clone();
+
+    run_headless_with_clock::<A, C, ManualClock>(frames, timestep_mode, clock, config, |frame| {
+        frame_clock.advance_ms(time_step);
+        input(frame)
+    })
+}
+
+// `input` runs before every frame and is also the place to advance or pause the clock.
+pub fn run_headless_with_clock<A: Application, C: ApplicationController<A>, K: Clock>(
+    frames: u32,
+    timestep_mode: TimeStep,
+    clock: K,
+    config: GraphicsConfig,
+    mut input: impl FnMut(u32) -> Vec<winit::event::WindowEvent<'static>>,
+) -> A {
+    timestep_mode
+        .validate()
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    let mut app = A::new(None, &config);
+    let mut controller = C::new(&mut app);
+    let mut frame_stepper = FrameStepper::new(&timestep_mode, clock);
+
+    for frame in 0..frames {
+        for event in input(frame).iter() {
+            app.handle_input(event);
+        }
+
+        frame_stepper.step(&mut app, &mut controller);
+    }
+
+    app
+}
+
 fn suspend_control_flow(control_flow: &mut winit::event_loop::ControlFlow) {
     *control_flow = {
         #[cfg(not(target_arch = "wasm32"))]
@@ -55,17 +175,21 @@ fn suspend_control_flow(control_flow: &mut winit::event_loop::ControlFlow) {
 
 /*--------------------------------------------------------------------------------------------------*/
 
-struct RedrawHandler {
+struct RedrawHandler<K: Clock> {
+    clock: K,
+
     #[cfg(not(target_arch = "wasm32"))]
-    previous: std::time::Instant,
+    previous: std::time::Duration,
 
     wait_ms: u64,
 }
 
-impl RedrawHandler {
-    fn new(fps: u32) -> Self {
+impl<K: Clock> RedrawHandler<K> {
+    fn new(fps: u32, clock: K) -> Self {
         Self {
-            previous: std::time::Instant::now(),
+            previous: clock.now(),
+            clock,
+
             wait_ms: (1000 / (fps + 2)) as u64,
         }
     }
@@ -73,9 +197,11 @@ impl RedrawHandler {
     fn request(&mut self, app: &dyn Application) {
         #[cfg(not(target_arch = "wasm32"))]
         {
-            if self.previous.elapsed() > std::time::Duration::from_millis(self.wait_ms) {
+            let now = self.clock.now();
+            // A manual clock can be set back in time, which must not underflow.
+            if now.saturating_sub(self.previous) > std::time::Duration::from_millis(self.wait_ms) {
                 app.request_redraw();
-                self.previous = std::time::Instant::now();
+                self.previous = now;
             }
         }
 
@@ -86,20 +212,338 @@ impl RedrawHandler {
 
 /*--------------------------------------------------------------------------------------------------*/
 
-struct TimeStepSupplier {
-    previous_redraw: std::time::Instant,
+struct FixedStepper {
+    delta: f32,
+    max_steps: u32,
+
+    accumulator: f32,
+}
+
+impl FixedStepper {
+    fn new(delta: f32, max_steps: u32) -> Self {
+        Self {
+            delta,
+            max_steps,
+
+            accumulator: 0.0,
+        }
+    }
+
+    fn advance(&mut self, elapsed: f32) -> u32 {
+        self.accumulator += elapsed;
+
+        let mut n_steps = 0;
+        while self.accumulator >= self.delta && n_steps < self.max_steps {
+            self.accumulator -= self.delta;
+            n_steps += 1;
+        }
+
+        // Drop the backlog instead of trying to catch up on it next frame.
+        if n_steps == self.max_steps {
+            self.accumulator = self.accumulator.min(self.delta);
+        }
+
+        n_steps
+    }
+
+    fn get_alpha(&self) -> f32 {
+        (self.accumulator / self.delta).min(1.0)
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+struct FrameStepper<K: Clock> {
+    timestep: TimeStepSupplier<K>,
+    fixed_stepper: Option<FixedStepper>,
 }
 
-impl TimeStepSupplier {
-    fn new() -> Self {
+impl<K: Clock> FrameStepper<K> {
+    fn new(timestep_mode: &TimeStep, clock: K) -> Self {
         Self {
-            previous_redraw: std::time::Instant::now(),
+            timestep: TimeStepSupplier::new(clock),
+            fixed_stepper: match *timestep_mode {
+                TimeStep::Variable => None,
+                TimeStep::Fixed { delta, max_steps } => Some(FixedStepper::new(delta, max_steps)),
+            },
+        }
+    }
+
+    fn step<A: Application, C: ApplicationController<A>>(
+        &mut self,
+        app: &mut A,
+        controller: &mut C,
+    ) {
+        match &mut self.fixed_stepper {
+            None => {
+                controller.step(app, self.timestep.step());
+                app.step(1.0);
+            }
+            Some(stepper) => {
+                let n_steps = stepper.advance(self.timestep.step());
+                for _ in 0..n_steps {
+                    controller.step(app, stepper.delta);
+                }
+                app.step(stepper.get_alpha());
+            }
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+struct TimeStepSupplier<K: Clock> {
+    clock: K,
+    previous_redraw: std::time::Duration,
+}
+
+impl<K: Clock> TimeStepSupplier<K> {
+    fn new(clock: K) -> Self {
+        Self {
+            previous_redraw: clock.now(),
+            clock,
         }
     }
 
     fn step(&mut self) -> f32 {
-        let timestep = (self.previous_redraw.elapsed().as_micros() as f32) / 1000.0;
-        self.previous_redraw = std::time::Instant::now();
-        timestep
+        let now = self.clock.now();
+        let elapsed = now.checked_sub(self.previous_redraw).unwrap_or_default();
+        self.previous_redraw = now;
+
+        (elapsed.as_micros() as f32) / 1000.0
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ScaledClock;
+
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct RecordingApp {
+        time_steps: Vec<f32>,
+        alphas: Vec<f32>,
+        input_frames: Vec<usize>,
+        redraws: std::cell::Cell<usize>,
+    }
+
+    impl Application for RecordingApp {
+        fn new(_: Option<&winit::event_loop::EventLoop<()>>, _: &GraphicsConfig) -> Self {
+            Self::default()
+        }
+
+        fn handle_input(&mut self, _: &winit::event::WindowEvent) {
+            self.input_frames.push(self.alphas.len());
+        }
+
+        fn request_redraw(&self) {
+            self.redraws.set(self.redraws.get() + 1);
+        }
+
+        fn step(&mut self, alpha: f32) {
+            self.alphas.push(alpha);
+        }
+    }
+
+    struct RecordingController;
+
+    impl ApplicationController<RecordingApp> for RecordingController {
+        fn new(_: &mut RecordingApp) -> Self {
+            Self
+        }
+
+        fn step(&mut self, app: &mut RecordingApp, time_step: f32) {
+            app.time_steps.push(time_step);
+        }
+    }
+
+    fn key_input(frame: u32) -> Vec<winit::event::WindowEvent<'static>> {
+        match frame {
+            1 | 3 => vec![winit::event::WindowEvent::ReceivedCharacter('x')],
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn headless_variable_steps_with_input() {
+        let app = run_headless_with_input::<RecordingApp, RecordingController>(
+            4,
+            16.0,
+            TimeStep::Variable,
+            GraphicsConfig::new(),
+            key_input,
+        );
+
+        assert_eq!(app.time_steps, vec![16.0; 4]);
+        assert_eq!(app.alphas, vec![1.0; 4]);
+        assert_eq!(app.input_frames, vec![1, 3]);
+    }
+
+    #[test]
+    fn headless_fixed_steps_interpolate() {
+        let app = run_headless_with_input::<RecordingApp, RecordingController>(
+            3,
+            16.0,
+            TimeStep::Fixed {
+                delta: 10.0,
+                max_steps: 4,
+            },
+            GraphicsConfig::new(),
+            key_input,
+        );
+
+        assert_eq!(app.time_steps, vec![10.0; 4]);
+        for (alpha, expected) in app.alphas.iter().zip([0.6, 0.2, 0.8].iter()) {
+            assert!((alpha - expected).abs() < 1e-5);
+        }
+        assert_eq!(app.input_frames, vec![1]);
+    }
+
+    #[test]
+    fn headless_respects_scale_and_pause() {
+        let source = ManualClock::new();
+        let clock = ScaledClock::new(source.clone());
+        let frame_clock = clock.clone();
+
+        let app = run_headless_with_clock::<RecordingApp, RecordingController, _>(
+            3,
+            TimeStep::Variable,
+            clock,
+            GraphicsConfig::new(),
+            |frame| {
+                match frame {
+                    1 => frame_clock.pause(),
+                    2 => {
+                        frame_clock.resume();
+                        frame_clock.set_scale(2.0);
+                    }
+                    _ => (),
+                }
+                source.advance_ms(10.0);
+
+                Vec::new()
+            },
+        );
+
+        assert_eq!(app.time_steps.len(), 3);
+        for (time_step, expected) in app.time_steps.iter().zip([10.0, 0.0, 20.0].iter()) {
+            assert!((time_step - expected).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn manual_clock_advances_only_when_told() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::from_secs(0));
+
+        clock.advance(Duration::from_millis(16));
+        clock.advance_ms(4.0);
+        assert_eq!(clock.now(), Duration::from_millis(20));
+
+        clock.set(Duration::from_secs(3));
+        assert_eq!(clock.clone().now(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn redraw_requests_survive_clock_going_back() {
+        let clock = ManualClock::new();
+        clock.set(Duration::from_secs(1));
+
+        let app = RecordingApp::default();
+        let mut handler = RedrawHandler::new(60, clock.clone());
+
+        clock.set(Duration::from_millis(500));
+        handler.request(&app);
+        assert_eq!(app.redraws.get(), 0);
+
+        clock.set(Duration::from_secs(2));
+        handler.request(&app);
+        assert_eq!(app.redraws.get(), 1);
+    }
+
+    // Scaling goes through f32 seconds, so scaled times are only compared to the microsecond.
+    fn assert_ms(time: Duration, expected: f32) {
+        assert!(
+            (time.as_micros() as f32 / 1000.0 - expected).abs() < 1e-2,
+            "{:?} != {} ms",
+            time,
+            expected
+        );
+    }
+
+    #[test]
+    fn scaled_clock_scales_and_pauses() {
+        let source = ManualClock::new();
+        let clock = ScaledClock::new(source.clone());
+
+        source.advance_ms(10.0);
+        assert_ms(clock.now(), 10.0);
+
+        clock.set_scale(0.5);
+        source.advance_ms(10.0);
+        assert_ms(clock.now(), 15.0);
+
+        clock.pause();
+        source.advance_ms(100.0);
+        assert!(clock.is_paused());
+        assert_ms(clock.now(), 15.0);
+
+        clock.resume();
+        clock.set_scale(2.0);
+        source.advance_ms(10.0);
+        assert_ms(clock.now(), 35.0);
+    }
+
+    #[test]
+    fn fixed_stepper_accumulates_partial_steps() {
+        let mut stepper = FixedStepper::new(10.0, 4);
+
+        assert_eq!(stepper.advance(6.0), 0);
+        assert!((stepper.get_alpha() - 0.6).abs() < 1e-5);
+
+        assert_eq!(stepper.advance(6.0), 1);
+        assert!((stepper.get_alpha() - 0.2).abs() < 1e-5);
+
+        assert_eq!(stepper.advance(25.0), 2);
+        assert!((stepper.get_alpha() - 0.7).abs() < 1e-5);
+    }
+
+    #[test]
+    fn fixed_stepper_clamps_the_backlog() {
+        let mut stepper = FixedStepper::new(10.0, 3);
+
+        assert_eq!(stepper.advance(1000.0), 3);
+        assert!(stepper.get_alpha() <= 1.0);
+
+        // Only what was kept after clamping carries over.
+        assert_eq!(stepper.advance(0.0), 1);
+        assert_eq!(stepper.advance(0.0), 0);
+    }
+
+    #[test]
+    fn time_step_validation() {
+        assert!(TimeStep::Variable.validate().is_ok());
+        assert!(TimeStep::Fixed {
+            delta: 16.0,
+            max_steps: 1
+        }
+        .validate()
+        .is_ok());
+        assert!(TimeStep::Fixed {
+            delta: 0.0,
+            max_steps: 1
+        }
+        .validate()
+        .is_err());
+        assert!(TimeStep::Fixed {
+            delta: 16.0,
+            max_steps: 0
+        }
+        .validate()
+        .is_err());
     }
 }
@@ -0,0 +1,740 @@
+use cgmath::{
+    Deg, InnerSpace, Matrix4, Point2, Point3, Rad, SquareMatrix, Transform, Vector3, Vector4,
+};
+
+use crate::{
+    error::Error,
+    graphics::GraphicsManager,
+    handle::{camera::CameraHandle, light::LightHandle, shape::ShapeHandle},
+    mesh::{FromMeshVertex, MeshData, MeshVertex},
+    pipeline::{Geometry, Index, Vertex},
+};
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[derive(Debug, Clone)]
+pub struct SceneMaterial {
+    pub name: Option<String>,
+
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+
+    pub base_color_texture: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScenePrimitive {
+    pub data: MeshData,
+    pub material: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<ScenePrimitive>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SceneCamera {
+    Perspective {
+        y_fov: Rad<f32>,
+        aspect_ratio: Option<f32>,
+        z_near: f32,
+        z_far: Option<f32>,
+    },
+    Orthographic {
+        x_mag: f32,
+        y_mag: f32,
+        z_near: f32,
+        z_far: f32,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum SceneLightKind {
+    Directional,
+    Point,
+    Spot {
+        inner_cone_angle: f32,
+        outer_cone_angle: f32,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneLight {
+    pub name: Option<String>,
+    pub kind: SceneLightKind,
+
+    pub color: Vector3<f32>,
+    pub intensity: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneNode {
+    pub name: Option<String>,
+
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+
+    pub local_transform: Matrix4<f32>,
+    pub world_transform: Matrix4<f32>,
+
+    pub mesh: Option<usize>,
+    pub camera: Option<usize>,
+    pub light: Option<usize>,
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+pub struct GltfScene {
+    pub nodes: Vec<SceneNode>,
+    pub roots: Vec<usize>,
+
+    pub meshes: Vec<SceneMesh>,
+    pub materials: Vec<SceneMaterial>,
+    pub cameras: Vec<SceneCamera>,
+    pub lights: Vec<SceneLight>,
+}
+
+impl GltfScene {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let (document, buffers, _) = ::gltf::import(path).map_err(|err| Error::Import {
+            path: String::from(path),
+            message: err.to_string(),
+        })?;
+
+        let mut scene = Self {
+            nodes: document.nodes().map(Self::read_node).collect(),
+            roots: Vec::new(),
+
+            meshes: document
+                .meshes()
+                .map(|mesh| Self::read_mesh(&mesh, &buffers, path))
+                .collect::<Result<Vec<SceneMesh>, Error>>()?,
+            materials: document.materials().map(Self::read_material).collect(),
+            cameras: document.cameras().map(Self::read_camera).collect(),
+            lights: document
+                .lights()
+                .map(|lights| lights.map(Self::read_light).collect())
+                .unwrap_or_default(),
+        };
+
+        for node in document.nodes() {
+            for child in node.children() {
+                scene.nodes[child.index()].parent = Some(node.index());
+            }
+        }
+        scene.roots = match document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+        {
+            Some(default_scene) => default_scene.nodes().map(|node| node.index()).collect(),
+            None => (0..scene.nodes.len())
+                .filter(|index| scene.nodes[*index].parent.is_none())
+                .collect(),
+        };
+        for root in scene.roots.clone() {
+            scene.update_world_transforms(root);
+        }
+
+        Ok(scene)
+    }
+
+    pub fn find_node(&self, name: &str) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.name.as_deref() == Some(name))
+    }
+
+    pub fn set_local_transform(&mut self, node: usize, transform: Matrix4<f32>) {
+        self.nodes[node].local_transform = transform;
+        self.update_world_transforms(node);
+    }
+
+    pub fn create_geometries<T: Vertex + FromMeshVertex, I: Index + std::convert::TryFrom<u32>>(
+        &self,
+        graphics: &GraphicsManager,
+    ) -> Result<Vec<Vec<Geometry>>, Error> {
+        self.meshes
+            .iter()
+            .map(|mesh| {
+                mesh.primitives
+                    .iter()
+                    .map(|primitive| -> Result<Geometry, Error> {
+                        let (vertices, indices) = primitive.data.get_geometry_data::<T, I>()?;
+                        Ok(graphics.create_geometry(vertices, indices))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Each primitive is drawn as its own entity (see `create_geometries`), so the material is
+    // picked per primitive of the node's mesh.
+    pub fn apply_to_shape(&self, node: usize, primitive: usize, shape: &mut ShapeHandle) {
+        let node = &self.nodes[node];
+        shape.set_model(node.world_transform);
+
+        let material = node
+            .mesh
+            .and_then(|mesh| self.meshes[mesh].primitives.get(primitive))
+            .and_then(|primitive| primitive.material);
+        if let Some(material) = material {
+            let [r, g, b, _] = self.materials[material].base_color;
+            shape.set_color(Vector3::new(r, g, b));
+        }
+    }
+
+    pub fn apply_to_camera(&self, node: usize, camera: &mut CameraHandle, aspect_ratio: f32) {
+        let node = &self.nodes[node];
+        let eye = node
+            .world_transform
+            .transform_point(Point3::new(0.0, 0.0, 0.0));
+        let direction = node.world_transform.transform_vector(-Vector3::unit_z());
+        camera.look_at_dir(eye, direction);
+
+        match node.camera.map(|index| &self.cameras[index]) {
+            Some(SceneCamera::Perspective {
+                y_fov,
+                aspect_ratio: scene_aspect_ratio,
+                z_near,
+                z_far,
+            }) => {
+                let angle: Deg<f32> = (*y_fov).into();
+                camera.set_perspective_range(
+                    angle.0,
+                    scene_aspect_ratio.unwrap_or(aspect_ratio),
+                    *z_near,
+                    *z_far,
+                );
+            }
+            Some(SceneCamera::Orthographic {
+                x_mag,
+                y_mag,
+                z_near,
+                z_far,
+            }) => {
+                camera.set_ortho(-x_mag, *x_mag, -y_mag, *y_mag, *z_near, *z_far);
+            }
+            None => (),
+        }
+    }
+
+    pub fn apply_to_light(&self, node: usize, light: &mut LightHandle) {
+        let node = &self.nodes[node];
+        let scene_light = match node.light {
+            Some(scene_light) => &self.lights[scene_light],
+            None => return,
+        };
+
+        // glTF lights shine along -Z of their node.
+        let position = node
+            .world_transform
+            .transform_point(Point3::new(0.0, 0.0, 0.0));
+        let direction = node.world_transform.transform_vector(-Vector3::unit_z());
+        match scene_light.kind {
+            SceneLightKind::Directional => {
+                light.set_direction(direction).clear_spot();
+            }
+            SceneLightKind::Point => {
+                light
+                    .set_position(Vector3::new(position.x, position.y, position.z))
+                    .clear_spot();
+            }
+            SceneLightKind::Spot {
+                inner_cone_angle,
+                outer_cone_angle,
+            } => {
+                light
+                    .set_position(Vector3::new(position.x, position.y, position.z))
+                    .set_spot(direction, inner_cone_angle, outer_cone_angle);
+            }
+        }
+        light
+            .set_color(scene_light.color)
+            .set_intensity(scene_light.intensity);
+    }
+
+    /*-------------------------------------------------*/
+
+    // Walks the subtree depth first without recursion; a node reached twice means the file has
+    // a cycle, which glTF forbids, and is skipped.
+    fn update_world_transforms(&mut self, node: usize) {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut visited[node], true) {
+                log::warn!(
+                    "Skipping glTF node {} found twice in its own hierarchy",
+                    node
+                );
+                continue;
+            }
+
+            let parent_transform = match self.nodes[node].parent {
+                Some(parent) => self.nodes[parent].world_transform,
+                None => Matrix4::identity(),
+            };
+            self.nodes[node].world_transform = parent_transform * self.nodes[node].local_transform;
+
+            stack.extend(self.nodes[node].children.iter().rev());
+        }
+    }
+
+    fn read_node(node: ::gltf::Node) -> SceneNode {
+        let local_transform = Matrix4::from(node.transform().matrix());
+        SceneNode {
+            name: node.name().map(String::from),
+
+            parent: None,
+            children: node.children().map(|child| child.index()).collect(),
+
+            local_transform,
+            world_transform: local_transform,
+
+            mesh: node.mesh().map(|mesh| mesh.index()),
+            camera: node.camera().map(|camera| camera.index()),
+            light: node.light().map(|light| light.index()),
+        }
+    }
+
+    fn read_mesh(
+        mesh: &::gltf::Mesh,
+        buffers: &[::gltf::buffer::Data],
+        path: &str,
+    ) -> Result<SceneMesh, Error> {
+        let mut primitives = Vec::new();
+        for primitive in mesh.primitives() {
+            if let Some(primitive) = Self::read_primitive(&primitive, buffers, path)? {
+                primitives.push(primitive);
+            }
+        }
+
+        Ok(SceneMesh {
+            name: mesh.name().map(String::from),
+            primitives,
+        })
+    }
+
+    // Returns `None` for primitives that aren't triangles, which are skipped.
+    fn read_primitive(
+        primitive: &::gltf::Primitive,
+        buffers: &[::gltf::buffer::Data],
+        path: &str,
+    ) -> Result<Option<ScenePrimitive>, Error> {
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+        let positions: Vec<[f32; 3]> = reader
+            .read_positions()
+            .ok_or_else(|| Error::Import {
+                path: String::from(path),
+                message: format!("Primitive {} has no POSITION attribute", primitive.index()),
+            })?
+            .collect();
+        let normals: Option<Vec<[f32; 3]>> = reader.read_normals().map(|n| n.collect());
+        let uvs: Option<Vec<[f32; 2]>> = reader
+            .read_tex_coords(0)
+            .map(|uvs| uvs.into_f32().collect());
+        let tangents: Option<Vec<[f32; 4]>> = reader.read_tangents().map(|t| t.collect());
+
+        let vertex_count = positions.len();
+        Self::check_attribute_count(path, "NORMAL", normals.as_ref().map(Vec::len), vertex_count)?;
+        Self::check_attribute_count(path, "TEXCOORD_0", uvs.as_ref().map(Vec::len), vertex_count)?;
+        Self::check_attribute_count(
+            path,
+            "TANGENT",
+            tangents.as_ref().map(Vec::len),
+            vertex_count,
+        )?;
+
+        let mut data = MeshData::new();
+        data.vertices = positions
+            .iter()
+            .enumerate()
+            .map(|(index, position)| {
+                let normal = normals.as_ref().map_or([0.0, 0.0, 0.0], |n| n[index]);
+                let uv = uvs.as_ref().map_or([0.0, 0.0], |uvs| uvs[index]);
+
+                MeshVertex::new(
+                    Point3::from(*position),
+                    Vector3::from(normal),
+                    Point2::from(uv),
+                )
+            })
+            .collect();
+
+        let indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+        if let Some(index) = indices
+            .iter()
+            .find(|index| **index as usize >= vertex_count)
+        {
+            return Err(Error::Import {
+                path: String::from(path),
+                message: format!(
+                    "Index {} is out of range for a primitive with {} vertices",
+                    index, vertex_count
+                ),
+            });
+        }
+
+        data.indices = match primitive.mode() {
+            ::gltf::mesh::Mode::Triangles => indices,
+            ::gltf::mesh::Mode::TriangleStrip => (0..indices.len().saturating_sub(2))
+                .flat_map(|i| {
+                    if i % 2 == 0 {
+                        vec![indices[i], indices[i + 1], indices[i + 2]]
+                    } else {
+                        vec![indices[i + 1], indices[i], indices[i + 2]]
+                    }
+                })
+                .collect(),
+            ::gltf::mesh::Mode::TriangleFan => (1..indices.len().saturating_sub(1))
+                .flat_map(|i| vec![indices[0], indices[i], indices[i + 1]])
+                .collect(),
+            mode => {
+                log::warn!("Skipping glTF primitive with mode {:?} [{}]", mode, path);
+                return Ok(None);
+            }
+        };
+
+        // Zero-length normals would normalize to NaN, they are rebuilt from the faces instead.
+        match normals {
+            None => data.compute_normals(),
+            Some(_) => {
+                let degenerate: Vec<bool> = data
+                    .vertices
+                    .iter()
+                    .map(|vertex| vertex.normal.magnitude2() <= std::f32::EPSILON)
+                    .collect();
+                data.vertices
+                    .iter_mut()
+                    .zip(degenerate.iter())
+                    .filter(|(_, degenerate)| !**degenerate)
+                    .for_each(|(vertex, _)| vertex.normal = vertex.normal.normalize());
+                data.compute_normals_where(|index| degenerate[index]);
+            }
+        }
+
+        match tangents {
+            Some(tangents) => data
+                .vertices
+                .iter_mut()
+                .zip(tangents)
+                .for_each(|(vertex, tangent)| vertex.tangent = Vector4::from(tangent)),
+            None => data.compute_tangents(),
+        }
+
+        Ok(Some(ScenePrimitive {
+            data,
+            material: primitive.material().index(),
+        }))
+    }
+
+    fn check_attribute_count(
+        path: &str,
+        semantic: &str,
+        count: Option<usize>,
+        vertex_count: usize,
+    ) -> Result<(), Error> {
+        match count {
+            Some(count) if count != vertex_count => Err(Error::Import {
+                path: String::from(path),
+                message: format!(
+                    "{} has {} elements but POSITION has {}",
+                    semantic, count, vertex_count
+                ),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_material(material: ::gltf::Material) -> SceneMaterial {
+        let pbr = material.pbr_metallic_roughness();
+        SceneMaterial {
+            name: material.name().map(String::from),
+
+            base_color: pbr.base_color_factor(),
+            metallic: pbr.metallic_factor(),
+            roughness: pbr.roughness_factor(),
+            emissive: material.emissive_factor(),
+
+            base_color_texture: pbr.base_color_texture().map(|info| info.texture().index()),
+        }
+    }
+
+    fn read_camera(camera: ::gltf::Camera) -> SceneCamera {
+        match camera.projection() {
+            ::gltf::camera::Projection::Perspective(perspective) => SceneCamera::Perspective {
+                y_fov: Rad(perspective.yfov()),
+                aspect_ratio: perspective.aspect_ratio(),
+                z_near: perspective.znear(),
+                z_far: perspective.zfar(),
+            },
+            ::gltf::camera::Projection::Orthographic(orthographic) => SceneCamera::Orthographic {
+                x_mag: orthographic.xmag(),
+                y_mag: orthographic.ymag(),
+                z_near: orthographic.znear(),
+                z_far: orthographic.zfar(),
+            },
+        }
+    }
+
+    fn read_light(light: ::gltf::khr_lights_punctual::Light) -> SceneLight {
+        SceneLight {
+            name: light.name().map(String::from),
+            kind: match light.kind() {
+                ::gltf::khr_lights_punctual::Kind::Directional => SceneLightKind::Directional,
+                ::gltf::khr_lights_punctual::Kind::Point => SceneLightKind::Point,
+                ::gltf::khr_lights_punctual::Kind::Spot {
+                    inner_cone_angle,
+                    outer_cone_angle,
+                } => SceneLightKind::Spot {
+                    inner_cone_angle,
+                    outer_cone_angle,
+                },
+            },
+
+            color: Vector3::from(light.color()),
+            intensity: light.intensity(),
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_node(parent: Option<usize>, children: Vec<usize>, x: f32) -> SceneNode {
+        let local_transform = Matrix4::from_translation(Vector3::new(x, 0.0, 0.0));
+        SceneNode {
+            name: None,
+
+            parent,
+            children,
+
+            local_transform,
+            world_transform: local_transform,
+
+            mesh: None,
+            camera: None,
+            light: None,
+        }
+    }
+
+    fn create_scene(nodes: Vec<SceneNode>) -> GltfScene {
+        GltfScene {
+            nodes,
+            roots: vec![0],
+
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            cameras: Vec::new(),
+            lights: Vec::new(),
+        }
+    }
+
+    fn get_x(scene: &GltfScene, node: usize) -> f32 {
+        scene.nodes[node].world_transform.w.x
+    }
+
+    // Writes a single primitive glTF file with its buffer next to it and returns its path.
+    fn write_gltf(
+        name: &str,
+        mode: u32,
+        positions: Option<&[[f32; 3]]>,
+        normals: Option<&[[f32; 3]]>,
+        indices: Option<&[u16]>,
+    ) -> String {
+        let dir = std::env::temp_dir().join(format!("brics_gltf_{}", name));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut data = Vec::<u8>::new();
+        let mut buffer_views = Vec::<String>::new();
+        let mut accessors = Vec::<String>::new();
+        let mut attributes = Vec::<String>::new();
+        let mut add_view = |data: &mut Vec<u8>, bytes: Vec<u8>| {
+            buffer_views.push(format!(
+                r#"{{"buffer": 0, "byteOffset": {}, "byteLength": {}}}"#,
+                data.len(),
+                bytes.len()
+            ));
+            data.extend(bytes);
+            buffer_views.len() - 1
+        };
+        let vec3_bytes = |values: &[[f32; 3]]| -> Vec<u8> {
+            values
+                .iter()
+                .flat_map(|value| value.iter().flat_map(|x| x.to_le_bytes().to_vec()))
+                .collect()
+        };
+
+        if let Some(positions) = positions {
+            let view = add_view(&mut data, vec3_bytes(positions));
+            let bound = |select: fn(f32, f32) -> f32| {
+                (0..3)
+                    .map(|axis| {
+                        positions
+                            .iter()
+                            .map(|position| position[axis])
+                            .fold(positions[0][axis], select)
+                            .to_string()
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
+            accessors.push(format!(
+                r#"{{"bufferView": {}, "componentType": 5126, "count": {}, "type": "VEC3", "min": [{}], "max": [{}]}}"#,
+                view,
+                positions.len(),
+                bound(f32::min),
+                bound(f32::max)
+            ));
+            attributes.push(format!(r#""POSITION": {}"#, accessors.len() - 1));
+        }
+        if let Some(normals) = normals {
+            let view = add_view(&mut data, vec3_bytes(normals));
+            accessors.push(format!(
+                r#"{{"bufferView": {}, "componentType": 5126, "count": {}, "type": "VEC3"}}"#,
+                view,
+                normals.len()
+            ));
+            attributes.push(format!(r#""NORMAL": {}"#, accessors.len() - 1));
+        }
+        let indices_entry = match indices {
+            Some(indices) => {
+                let bytes = indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes().to_vec())
+                    .collect();
+                let view = add_view(&mut data, bytes);
+                accessors.push(format!(
+                    r#"{{"bufferView": {}, "componentType": 5123, "count": {}, "type": "SCALAR"}}"#,
+                    view,
+                    indices.len()
+                ));
+                format!(r#", "indices": {}"#, accessors.len() - 1)
+            }
+            None => String::new(),
+        };
+
+        let bin_name = format!("{}.bin", name);
+        std::fs::write(dir.join(&bin_name), &data).unwrap();
+
+        let json = format!(
+            r#"{{
+                "asset": {{"version": "2.0"}},
+                "buffers": [{{"uri": "{}", "byteLength": {}}}],
+                "bufferViews": [{}],
+                "accessors": [{}],
+                "meshes": [{{"primitives": [{{"attributes": {{{}}}, "mode": {}{}}}]}}],
+                "nodes": [{{"mesh": 0}}],
+                "scenes": [{{"nodes": [0]}}],
+                "scene": 0
+            }}"#,
+            bin_name,
+            data.len(),
+            buffer_views.join(", "),
+            accessors.join(", "),
+            attributes.join(", "),
+            mode,
+            indices_entry
+        );
+        let path = dir.join(format!("{}.gltf", name));
+        std::fs::write(&path, json).unwrap();
+
+        path.to_string_lossy().into_owned()
+    }
+
+    const QUAD: [[f32; 3]; 4] = [
+        [0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+    ];
+
+    fn load_indices(path: &str) -> Vec<u32> {
+        GltfScene::load(path).unwrap().meshes[0].primitives[0]
+            .data
+            .indices
+            .clone()
+    }
+
+    #[test]
+    fn propagates_local_transforms_to_descendants() {
+        let mut scene = create_scene(vec![
+            create_node(None, vec![1], 1.0),
+            create_node(Some(0), vec![2], 2.0),
+            create_node(Some(1), vec![], 4.0),
+        ]);
+
+        scene.set_local_transform(0, Matrix4::from_translation(Vector3::new(8.0, 0.0, 0.0)));
+        assert_eq!(get_x(&scene, 0), 8.0);
+        assert_eq!(get_x(&scene, 1), 10.0);
+        assert_eq!(get_x(&scene, 2), 14.0);
+    }
+
+    #[test]
+    fn stops_at_cycles() {
+        let mut scene = create_scene(vec![
+            create_node(Some(1), vec![1], 1.0),
+            create_node(Some(0), vec![0], 2.0),
+        ]);
+
+        scene.set_local_transform(0, Matrix4::from_translation(Vector3::new(8.0, 0.0, 0.0)));
+        // Each node is visited once, node 0 still sees the stale transform of its parent.
+        assert_eq!(get_x(&scene, 0), 10.0);
+        assert_eq!(get_x(&scene, 1), 12.0);
+    }
+
+    #[test]
+    fn converts_strips_and_fans_to_triangle_lists() {
+        let strip = write_gltf("strip", 5, Some(&QUAD), None, None);
+        assert_eq!(load_indices(&strip), vec![0, 1, 2, 2, 1, 3]);
+
+        let fan = write_gltf("fan", 6, Some(&QUAD), None, Some(&[0, 1, 3, 2]));
+        assert_eq!(load_indices(&fan), vec![0, 1, 3, 0, 3, 2]);
+    }
+
+    #[test]
+    fn generates_missing_normals() {
+        let path = write_gltf(
+            "no_normals",
+            4,
+            Some(&QUAD),
+            None,
+            Some(&[0, 1, 2, 2, 1, 3]),
+        );
+        let scene = GltfScene::load(&path).unwrap();
+
+        for vertex in scene.meshes[0].primitives[0].data.vertices.iter() {
+            assert!((vertex.normal - Vector3::new(0.0, 0.0, 1.0)).magnitude() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range_indices() {
+        let path = write_gltf("bad_index", 4, Some(&QUAD), None, Some(&[0, 1, 4]));
+        assert!(matches!(GltfScene::load(&path), Err(Error::Import { .. })));
+    }
+
+    #[test]
+    fn rejects_short_attributes() {
+        let normals = [[0.0, 0.0, 1.0]; 3];
+        let path = write_gltf("short_normals", 4, Some(&QUAD), Some(&normals), None);
+        assert!(matches!(GltfScene::load(&path), Err(Error::Import { .. })));
+    }
+
+    #[test]
+    fn rejects_primitives_without_positions() {
+        let normals = [[0.0, 0.0, 1.0]; 3];
+        let path = write_gltf("no_positions", 4, None, Some(&normals), None);
+        assert!(matches!(GltfScene::load(&path), Err(Error::Import { .. })));
+    }
+}
@@ -1,47 +1,41 @@
 use super::application::Application;
 use super::rcmut::RcMut;
 
-use std::marker::PhantomData;
+use std::cell::RefMut;
 
-use std::borrow::BorrowMut;
-
-pub trait Script {
-    fn update(&mut self);
+pub trait Script<A: Application> {
+    fn update(&mut self, app: &mut A);
 }
 
 /*------------------------------------------------------------------------*/
 
-pub struct ApplicationController<A: Application> {
-    app: A,
-    controller: Box<dyn FnMut(&mut A) + 'static>,
+pub struct LogicScript<A: Application> {
+    logic: Box<dyn FnMut(&mut A) + 'static>,
 }
 
-impl<A: Application> ApplicationController<A> {
-    pub fn new(controller: impl FnMut(&mut A) + 'static) -> Self {
+impl<A: Application> LogicScript<A> {
+    pub fn new(logic: impl FnMut(&mut A) + 'static) -> Self {
         Self {
-            
-            controller: Box::new(controller),
+            logic: Box::new(logic),
         }
     }
 }
 
-impl<A: Application> Script<A> for ApplicationController<A> {
-    fn update(&mut self) {
-        self.controller.as_mut()(app);
+impl<A: Application> Script<A> for LogicScript<A> {
+    fn update(&mut self, app: &mut A) {
+        self.logic.as_mut()(app);
     }
 }
 
 /*------------------------------------------------------------------------*/
 
-pub trait Object {}
-
-pub struct ObjectController<O: Object> {
+pub struct ObjectController<O: 'static, A: Application> {
     object: RcMut<O>,
-    controller: Box<dyn FnMut(&mut O) + 'static>,
+    controller: Box<dyn FnMut(RefMut<O>, &mut A) + 'static>,
 }
 
-impl<O: Object> ObjectController<O> {
-    pub fn new(object: RcMut<O>, controller: impl FnMut(&mut O) + 'static) -> Self {
+impl<O: 'static, A: Application> ObjectController<O, A> {
+    pub fn new(object: RcMut<O>, controller: impl FnMut(RefMut<O>, &mut A) + 'static) -> Self {
         Self {
             object,
             controller: Box::new(controller),
@@ -49,8 +43,8 @@ impl<O: Object> ObjectController<O> {
     }
 }
 
-impl<O: Object> Script for ObjectController<O> {
-    fn update(&mut self) {
-        self.controller.as_mut()(&mut self.object);
+impl<O: 'static, A: Application> Script<A> for ObjectController<O, A> {
+    fn update(&mut self, app: &mut A) {
+        self.controller.as_mut()(self.object.borrow_mut(), app);
     }
 }
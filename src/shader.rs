@@ -1,14 +1,20 @@
 use std::convert::From;
 use wgpu;
 
+use crate::error::Error;
+
 pub struct ShaderCompiler {
     compiler: shaderc::Compiler,
 }
 
 impl ShaderCompiler {
     pub fn new() -> Self {
-        let compiler = shaderc::Compiler::new().unwrap();
-        Self { compiler }
+        Self::try_new().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_new() -> Result<Self, Error> {
+        let compiler = shaderc::Compiler::new().ok_or(Error::ShaderCompiler)?;
+        Ok(Self { compiler })
     }
 
     pub fn compile(
@@ -16,18 +22,56 @@ impl ShaderCompiler {
         path: &str,
         shader_kind: shaderc::ShaderKind,
     ) -> wgpu::ShaderModuleSource {
+        self.try_compile(path, shader_kind)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compile(
+        &mut self,
+        path: &str,
+        shader_kind: shaderc::ShaderKind,
+    ) -> Result<wgpu::ShaderModuleSource<'static>, Error> {
         println!("Compiling shader with path [{}]", path);
 
-        let file_content =
-            std::fs::read_to_string(path).expect(&format!("Unsuccessful file reading [{}].", path));
+        let file_content = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: String::from(path),
+            source,
+        })?;
 
+        self.try_compile_source(&file_content, path, shader_kind)
+    }
+
+    pub fn compile_source(
+        &mut self,
+        source: &str,
+        name: &str,
+        shader_kind: shaderc::ShaderKind,
+    ) -> wgpu::ShaderModuleSource {
+        self.try_compile_source(source, name, shader_kind)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_compile_source(
+        &mut self,
+        source: &str,
+        name: &str,
+        shader_kind: shaderc::ShaderKind,
+    ) -> Result<wgpu::ShaderModuleSource<'static>, Error> {
         let artifact: shaderc::CompilationArtifact = self
             .compiler
-            .compile_into_spirv(&file_content, shader_kind, "shader.glsl", "main", None)
-            .unwrap();
+            .compile_into_spirv(source, shader_kind, name, "main", None)
+            .map_err(|err| Error::ShaderCompilation {
+                path: String::from(name),
+                log: match err {
+                    shaderc::Error::CompilationError(_, log) => log,
+                    _ => err.to_string(),
+                },
+            })?;
 
         let binary = artifact.as_binary();
 
-        wgpu::ShaderModuleSource::SpirV(std::borrow::Cow::Owned(Vec::from(&binary[..])))
+        Ok(wgpu::ShaderModuleSource::SpirV(std::borrow::Cow::Owned(
+            Vec::from(&binary[..]),
+        )))
     }
 }
@@ -0,0 +1,156 @@
+use cgmath::{Point3, Vector3};
+
+use crate::{
+    binding::sampler::{SamplerAddressMode, SamplerFilterMode},
+    error::Error,
+    graphics::GraphicsManager,
+    handle::{
+        camera::{CameraHandle, CameraHandleLayout},
+        sampler::SamplerHandleLayout,
+        texture::{TextureHandle, TextureHandleLayout},
+        BindingHandleLayout, RcBinding,
+    },
+    mesh::{FromMeshVertex, MeshData, MeshVertex},
+    pipeline::{BindingLayoutEntries, Vertex},
+    render_pass::{AttachmentView, RenderPass},
+};
+
+const SKYBOX_VERTEX_SHADER: &str = "
+#version 450
+
+layout(location = 0) in vec3 a_Pos;
+
+layout(location = 0) out vec3 v_Direction;
+
+layout(set = 0, binding = 0) uniform u_Camera {
+    mat4 m_in_PV;
+    vec3 v_in_CamPosition;
+};
+
+void main() {
+    v_Direction = a_Pos;
+    // centered on the eye so only the camera rotation matters, pinned to the far plane
+    gl_Position = (m_in_PV * vec4(v_in_CamPosition + a_Pos, 1.0)).xyww;
+}
+";
+
+const SKYBOX_FRAGMENT_SHADER: &str = "
+#version 450
+
+layout(location = 0) in vec3 v_Direction;
+
+layout(location = 0) out vec4 o_Target;
+
+layout(set = 0, binding = 1) uniform textureCube t_Sky;
+layout(set = 0, binding = 2) uniform sampler s_Sky;
+
+void main() {
+    o_Target = texture(samplerCube(t_Sky, s_Sky), v_Direction);
+}
+";
+
+pub struct SkyboxVertex {
+    _position: Point3<f32>,
+}
+
+impl Vertex for SkyboxVertex {
+    fn get_attribute_formats() -> Vec<wgpu::VertexFormat> {
+        vec![wgpu::VertexFormat::Float3]
+    }
+}
+
+impl FromMeshVertex for SkyboxVertex {
+    fn from_mesh_vertex(vertex: &MeshVertex) -> Self {
+        Self {
+            _position: vertex.position,
+        }
+    }
+}
+
+/*--------------------------------------------------------------------------------------------------*/
+
+// Clears the output and draws the cube map; scene passes added after it should load the color
+// attachment instead of clearing it.
+pub fn create_render_pass(
+    graphics: &GraphicsManager,
+    camera: &RcBinding<CameraHandle>,
+    cubemap_layout: &TextureHandleLayout,
+    cubemap: &RcBinding<TextureHandle>,
+) -> RenderPass {
+    try_create_render_pass(graphics, camera, cubemap_layout, cubemap)
+        .unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub fn try_create_render_pass(
+    graphics: &GraphicsManager,
+    camera: &RcBinding<CameraHandle>,
+    cubemap_layout: &TextureHandleLayout,
+    cubemap: &RcBinding<TextureHandle>,
+) -> Result<RenderPass, Error> {
+    if cubemap_layout.get_binding_layout().get_view_dimension() != wgpu::TextureViewDimension::Cube
+    {
+        return Err(Error::Validation(String::from(
+            "Skybox texture layout is not a cube map",
+        )));
+    }
+
+    let camera_layout = CameraHandleLayout::new(wgpu::ShaderStage::VERTEX);
+    let sampler_layout = SamplerHandleLayout::new(
+        wgpu::ShaderStage::FRAGMENT,
+        SamplerAddressMode {
+            u: wgpu::AddressMode::ClampToEdge,
+            v: wgpu::AddressMode::ClampToEdge,
+            w: wgpu::AddressMode::ClampToEdge,
+        },
+        SamplerFilterMode {
+            mag: wgpu::FilterMode::Linear,
+            min: wgpu::FilterMode::Linear,
+            mipmap: wgpu::FilterMode::Linear,
+        },
+        None,
+    );
+    let sampler = RcBinding::new(sampler_layout.create_handle(graphics));
+
+    let mut pipeline = graphics.try_create_pipeline_from_source::<SkyboxVertex, u16>(
+        SKYBOX_VERTEX_SHADER,
+        SKYBOX_FRAGMENT_SHADER,
+        BindingLayoutEntries::new()
+            .add(&camera_layout)
+            .add(cubemap_layout)
+            .add(&sampler_layout),
+        wgpu::PrimitiveTopology::TriangleList,
+        Some(wgpu::ColorStateDescriptor {
+            format: graphics.get_output_format(),
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }),
+        None,
+        Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            ..Default::default()
+        }),
+    )?;
+
+    let (vertices, indices) = MeshData::cuboid(Vector3::new(2.0, 2.0, 2.0), 1)
+        .get_geometry_data::<SkyboxVertex, u16>()?;
+    let geometry = graphics.create_geometry(vertices, indices);
+    graphics.try_add_pipeline_entity(
+        &mut pipeline,
+        &geometry,
+        vec![camera.share(), cubemap.share(), sampler.share()],
+    )?;
+
+    let mut rpass = RenderPass::new();
+    rpass.set_color_attachment(
+        AttachmentView::Dynamic,
+        wgpu::Operations {
+            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            store: true,
+        },
+    );
+    rpass.add_pipeline(pipeline);
+
+    Ok(rpass)
+}